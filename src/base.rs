@@ -1,5 +1,8 @@
 pub mod board;
 pub mod encode_decode;
+pub mod statistics;
+pub mod timer;
 mod vec2d;
+pub mod zini;
 
 pub use vec2d::Vec2D;
@@ -1,6 +1,7 @@
 use super::Vec2D;
-use log::{debug, info};
-use rand::{rng, seq::SliceRandom};
+use log::{debug, error, info};
+use rand::{Rng, SeedableRng, rng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CellState {
@@ -26,7 +27,21 @@ pub enum ChordMode {
     LeftClick,
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How the first left click constrains mine placement.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FirstClickPolicy {
+    /// Mines are placed without looking at the first click.
+    None,
+    /// The clicked cell is never a mine.
+    #[default]
+    SafeCell,
+    /// The clicked cell and its neighbours are never mines, so the first click opens an area.
+    Opening,
+    /// No cell in the clicked row is a mine.
+    SafeRow,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum BoardState {
     #[default]
     NotStarted,
@@ -52,6 +67,46 @@ impl CellContent {
     }
 }
 
+impl FirstClickPolicy {
+    pub const ALL: [FirstClickPolicy; 4] = [
+        FirstClickPolicy::None,
+        FirstClickPolicy::SafeCell,
+        FirstClickPolicy::Opening,
+        FirstClickPolicy::SafeRow,
+    ];
+
+    /// Whether the cell at `(x, y)` must be kept free of mines when the first click is at
+    /// `(cx, cy)`.
+    pub fn keeps_clear(self, (cx, cy): (usize, usize), (x, y): (usize, usize)) -> bool {
+        match self {
+            FirstClickPolicy::None => false,
+            FirstClickPolicy::SafeCell => (x, y) == (cx, cy),
+            FirstClickPolicy::Opening => x.abs_diff(cx) <= 1 && y.abs_diff(cy) <= 1,
+            FirstClickPolicy::SafeRow => y == cy,
+        }
+    }
+
+    /// The weaker policy to try when there is not enough room for the mines under this one.
+    pub fn fallback(self) -> Self {
+        match self {
+            FirstClickPolicy::SafeRow => FirstClickPolicy::Opening,
+            FirstClickPolicy::Opening => FirstClickPolicy::SafeCell,
+            FirstClickPolicy::SafeCell | FirstClickPolicy::None => FirstClickPolicy::None,
+        }
+    }
+}
+
+impl std::fmt::Display for FirstClickPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FirstClickPolicy::None => write!(f, "None"),
+            FirstClickPolicy::SafeCell => write!(f, "Safe Cell"),
+            FirstClickPolicy::Opening => write!(f, "Opening"),
+            FirstClickPolicy::SafeRow => write!(f, "Safe Row"),
+        }
+    }
+}
+
 impl BoardState {
     pub fn is_end(&self) -> bool {
         matches!(self, BoardState::Won | BoardState::Lost { .. })
@@ -107,10 +162,54 @@ impl BoardState {
     }
 }
 
+/// Everything needed to place the mines of a board again, see [`BoardSeed::layout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardSeed {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub seed: u64,
+    pub first_click: Option<(usize, usize)>,
+    pub policy: FirstClickPolicy,
+}
+
+impl BoardSeed {
+    /// Place the mines exactly as the board this seed was taken from.
+    ///
+    /// The RNG is seeded with `seed`, so the same seed always gives the same layout.
+    pub fn layout(&self) -> Vec2D<CellContent> {
+        let mut cell_contents = Vec2D::new(self.width, self.height);
+        place_mines(
+            &mut cell_contents,
+            self.mines,
+            self.first_click,
+            self.policy,
+            &mut ChaCha8Rng::seed_from_u64(self.seed),
+        );
+        cell_contents
+    }
+
+    /// The layout to import, with the first click as the start position.
+    pub fn import_pack(&self) -> ImportPack {
+        ImportPack {
+            cell_contents: self.layout(),
+            mines: self.mines,
+            start_position: self.first_click,
+            cell_states: None,
+            seed: Some(*self),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ImportPack {
     pub cell_contents: Vec2D<CellContent>,
     pub mines: usize,
     pub start_position: Option<(usize, usize)>,
+    /// The cells as the player left them, `None` if every cell is closed.
+    pub cell_states: Option<Vec2D<CellState>>,
+    /// The seed the layout was generated from, if any.
+    pub seed: Option<BoardSeed>,
 }
 
 /// Build number cells based on mine positions.
@@ -173,6 +272,54 @@ pub fn build_numbers(cell_content: &mut Vec2D<CellContent>, mines: usize) {
     }
 }
 
+/// Randomly place `mines` mines on an empty board and build the number cells.
+///
+/// If `click_position` is given, the cells required by `policy` are kept free of mines. When there
+/// is not enough room for that, weaker policies are tried in turn (see
+/// `FirstClickPolicy::fallback`). Returns the policy that was actually applied.
+pub fn place_mines(
+    cell_contents: &mut Vec2D<CellContent>,
+    mines: usize,
+    click_position: Option<(usize, usize)>,
+    mut policy: FirstClickPolicy,
+    rng: &mut impl Rng,
+) -> FirstClickPolicy {
+    let (width, height) = cell_contents.dims();
+    let mines = mines.min(cell_contents.len());
+    let Some(click_position) = click_position.filter(|&(cx, cy)| cx < width && cy < height) else {
+        policy = FirstClickPolicy::None;
+        cell_contents.fill(CellContent::Empty);
+        cell_contents.data_mut()[..mines].fill(CellContent::Mine);
+        cell_contents.data_mut().shuffle(rng);
+        build_numbers(cell_contents, mines);
+        return policy;
+    };
+
+    let mut candidates = loop {
+        let candidates: Vec<usize> = (0..cell_contents.len())
+            .filter(|&i| !policy.keeps_clear(click_position, (i % width, i / width)))
+            .collect();
+        if candidates.len() >= mines {
+            break candidates;
+        }
+        debug!(
+            "Not enough room for {} mines with first click policy {:?}, falling back to {:?}",
+            mines,
+            policy,
+            policy.fallback()
+        );
+        policy = policy.fallback();
+    };
+
+    cell_contents.fill(CellContent::Empty);
+    let (chosen, _) = candidates.partial_shuffle(rng, mines);
+    for &i in chosen.iter() {
+        cell_contents.data_mut()[i] = CellContent::Mine;
+    }
+    build_numbers(cell_contents, mines);
+    policy
+}
+
 pub trait Board {
     /// Get the width of the board.
     fn width(&self) -> usize;
@@ -189,6 +336,13 @@ pub trait Board {
     /// Get the current state of the board.
     fn state(&self) -> BoardState;
 
+    /// The seed the mines are placed with, `None` if the layout was not generated from one.
+    fn seed(&self) -> Option<u64>;
+
+    /// How to generate the same layout again, `None` until the mines are placed or if the layout
+    /// was not generated from a seed.
+    fn board_seed(&self) -> Option<BoardSeed>;
+
     /// Set the chord mode of the board.
     ///
     /// *Note*: The caller does *NOT* guarantee that mouse events are interpreted according to the
@@ -198,6 +352,14 @@ pub trait Board {
     /// Get the current chord mode of the board.
     fn chord_mode(&self) -> ChordMode;
 
+    /// Set the policy used to place mines around the first click.
+    ///
+    /// Only takes effect if the board has not been started yet.
+    fn set_first_click_policy(&mut self, policy: FirstClickPolicy);
+
+    /// Get the current first click policy of the board.
+    fn first_click_policy(&self) -> FirstClickPolicy;
+
     /// Perform a left click on the cell at `(x, y)`.
     fn left_click(&mut self, x: usize, y: usize) -> bool;
 
@@ -238,6 +400,27 @@ pub trait Board {
 
     /// Continue playing only when the board is in `BoardState::Lost` state.
     fn resume(&mut self);
+
+    /// Undo the last action, i.e. a click or a resume, returning `false` if there is none.
+    ///
+    /// Undoing the first click keeps the mines where they have been placed.
+    fn undo(&mut self) -> bool;
+
+    /// Redo the last undone action, returning `false` if there is none.
+    fn redo(&mut self) -> bool;
+
+    fn can_undo(&self) -> bool;
+
+    fn can_redo(&self) -> bool;
+}
+
+/// The changes made by one action on the board.
+#[derive(Clone, Debug)]
+struct Delta {
+    /// Changed cells with their states before and after the action, in order.
+    cells: Vec<((usize, usize), CellState, CellState)>,
+    state_before: BoardState,
+    state_after: BoardState,
 }
 
 #[derive(Clone, Debug)]
@@ -245,8 +428,16 @@ pub struct StandardBoard {
     cell_contents: Vec2D<CellContent>,
     mines: usize,
     chord_mode: ChordMode,
+    first_click_policy: FirstClickPolicy,
     state: BoardState,
     cell_states: Vec2D<CellState>,
+    start_position: Option<(usize, usize)>,
+    seed: Option<u64>,
+    board_seed: Option<BoardSeed>,
+    undo_stack: Vec<Delta>,
+    redo_stack: Vec<Delta>,
+    /// The action being performed, if any.
+    pending: Option<Delta>,
 }
 
 impl StandardBoard {
@@ -268,154 +459,129 @@ impl StandardBoard {
             self.mines,
             click_position
         );
-        let mut rng = rng();
-        self.cell_contents.data_mut()[..self.mines]
-            .iter_mut()
-            .for_each(|c| *c = CellContent::Mine);
-
-        if self.mines == self.cell_contents.len() {
-            self.state = BoardState::InProgress {
-                opened_cells: 0,
-                flags: 0,
-            };
-            return;
-        }
-
-        if let Some((cx, cy)) = click_position
-            && let Some(click_index) = self.index(cx, cy)
-        {
-            let len = self.cell_contents.len();
-            let data = self.cell_contents.data_mut();
-            if cx == 0 && cy == 0 {
-                // Clicked on top-left corner
-                data.swap(0, len - 1);
-                data[1..len].shuffle(&mut rng);
-            } else {
-                data[..len - 1].shuffle(&mut rng);
-                data[click_index..].rotate_right(1);
-                data[click_index] = CellContent::Empty;
-            }
-        } else {
-            self.cell_contents.data_mut().shuffle(&mut rng);
+        let seed = *self.seed.get_or_insert_with(|| rng().random());
+        let board_seed = BoardSeed {
+            width: self.cell_contents.dims().0,
+            height: self.cell_contents.dims().1,
+            mines: self.mines,
+            seed,
+            first_click: click_position,
+            policy: self.first_click_policy,
+        };
+        let applied = place_mines(
+            &mut self.cell_contents,
+            self.mines,
+            click_position,
+            self.first_click_policy,
+            &mut ChaCha8Rng::seed_from_u64(seed),
+        );
+        self.board_seed = Some(board_seed);
+        if click_position.is_some() && applied != self.first_click_policy {
+            info!(
+                "First click policy {:?} not possible with {} mines, applied {:?} instead",
+                self.first_click_policy, self.mines, applied
+            );
         }
 
-        build_numbers(&mut self.cell_contents, self.mines);
-
         self.state = BoardState::InProgress {
             opened_cells: 0,
             flags: 0,
         };
     }
 
-    fn open(&mut self, x: usize, y: usize) {
-        if self.state.is_end() {
-            return;
+    /// Run `action` as a single undoable action. Nested calls are part of the outer action.
+    fn record<R>(&mut self, action: impl FnOnce(&mut Self) -> R) -> R {
+        if self.pending.is_some() {
+            return action(self);
         }
-        let Some(cell_state) = self.cell_states.get_mut(x, y) else {
-            return;
+        self.pending = Some(Delta {
+            cells: Vec::new(),
+            state_before: self.state,
+            state_after: self.state,
+        });
+        let result = action(self);
+        if let Some(mut delta) = self.pending.take() {
+            delta.state_after = self.state;
+            if !delta.cells.is_empty() || delta.state_before != delta.state_after {
+                self.redo_stack.clear();
+                self.undo_stack.push(delta);
+            }
+        }
+        result
+    }
+
+    fn set_cell_state(&mut self, x: usize, y: usize, state: CellState) {
+        let before = std::mem::replace(&mut self.cell_states[(x, y)], state);
+        if let Some(delta) = &mut self.pending {
+            delta.cells.push(((x, y), before, state));
+        }
+    }
+
+    /// Open `cells` in order, flooding through empty cells, until a mine is hit.
+    ///
+    /// The flood uses an explicit work list so that huge openings cannot overflow the stack. A cell
+    /// is opened as soon as it is reached, so it enters the work list at most once. The win is not
+    /// checked here but once per action by the caller.
+    fn open(&mut self, cells: &[(usize, usize)]) {
+        let (width, height) = self.cell_contents.dims();
+        let mut pending = Vec::new();
+        for &(x, y) in cells {
+            if self.state.is_end() {
+                return;
+            }
+            if self.open_cell(x, y) {
+                pending.push((x, y));
+            }
+            // Neighbours of an empty cell are never mines
+            while let Some((x, y)) = pending.pop() {
+                for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                    for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                        if self.open_cell(nx, ny) {
+                            pending.push((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Open a closed or flagged cell, returning whether it is empty so the flood goes on from it.
+    fn open_cell(&mut self, x: usize, y: usize) -> bool {
+        let Some(&cell_state) = self.cell_states.get(x, y) else {
+            return false;
         };
-        if *cell_state != CellState::Closed && *cell_state != CellState::Flagged {
-            return;
+        if cell_state != CellState::Closed && cell_state != CellState::Flagged {
+            return false;
         }
         match self.cell_contents[(x, y)] {
             CellContent::Mine => {
                 // It's usually not possible to reach here when `CellState::Flagged`, but just in case
-                if *cell_state == CellState::Closed {
-                    *cell_state = CellState::Blasted;
+                if cell_state == CellState::Closed {
+                    self.set_cell_state(x, y, CellState::Blasted);
                     self.state.blast(x, y);
                 }
+                false
             },
             CellContent::Number(n) => {
-                if *cell_state == CellState::Flagged {
+                if cell_state == CellState::Flagged {
                     self.state.flag_removed();
                 }
-                *cell_state = CellState::Opening(n);
+                self.set_cell_state(x, y, CellState::Opening(n));
                 self.state.cell_opened();
-                self.state.check_win(self.cell_contents.len(), self.mines);
+                false
             },
             CellContent::Empty => {
-                if *cell_state == CellState::Flagged {
+                if cell_state == CellState::Flagged {
                     self.state.flag_removed();
                 }
-                *cell_state = CellState::Opening(0);
+                self.set_cell_state(x, y, CellState::Opening(0));
                 self.state.cell_opened();
-                self.state.check_win(self.cell_contents.len(), self.mines);
-                for dy in [-1isize, 0, 1] {
-                    for dx in [-1isize, 0, 1] {
-                        if dx == 0 && dy == 0 {
-                            continue;
-                        }
-                        let nx = x as isize + dx;
-                        let ny = y as isize + dy;
-                        if nx >= 0 && ny >= 0 {
-                            self.open(nx as usize, ny as usize);
-                        }
-                    }
-                }
+                true
             },
         }
     }
 
-    pub fn new(mut width: usize, mut height: usize, mines: usize, chord_mode: ChordMode) -> Self {
-        width = width.max(1);
-        height = height.max(1);
-        Self {
-            cell_contents: Vec2D::new(width, height),
-            mines: mines.clamp(1, width * height),
-            chord_mode,
-            state: BoardState::NotStarted,
-            cell_states: Vec2D::new(width, height),
-        }
-    }
-
-    pub fn import(pack: ImportPack, chord_mode: ChordMode) -> Option<Self> {
-        let ImportPack {
-            cell_contents, mines, ..
-        } = pack;
-        let (width, height) = cell_contents.dims();
-        Some(Self {
-            cell_contents,
-            mines,
-            chord_mode,
-            state: BoardState::InProgress {
-                opened_cells: 0,
-                flags: 0,
-            },
-            cell_states: Vec2D::new(width, height),
-        })
-    }
-}
-
-impl Board for StandardBoard {
-    fn width(&self) -> usize {
-        self.cell_contents.dims().0
-    }
-
-    fn height(&self) -> usize {
-        self.cell_contents.dims().1
-    }
-
-    fn mines(&self) -> usize {
-        self.mines
-    }
-
-    fn start_position(&self) -> Option<(usize, usize)> {
-        None
-    }
-
-    fn state(&self) -> BoardState {
-        self.state
-    }
-
-    fn set_chord_mode(&mut self, mode: ChordMode) {
-        self.chord_mode = mode;
-    }
-
-    fn chord_mode(&self) -> ChordMode {
-        self.chord_mode
-    }
-
-    fn left_click(&mut self, x: usize, y: usize) -> bool {
+    fn do_left_click(&mut self, x: usize, y: usize) -> bool {
         if self.state.is_end() {
             return self.state.is_end();
         }
@@ -426,13 +592,14 @@ impl Board for StandardBoard {
         if self.chord_mode == ChordMode::LeftClick
             && let CellState::Opening(1..) = self.cell_states[(x, y)]
         {
-            return self.chord_click(x, y, true);
+            return self.do_chord_click(x, y, true);
         }
-        self.open(x, y);
+        self.open(&[(x, y)]);
+        self.state.check_win(self.cell_contents.len(), self.mines);
         self.state.is_end()
     }
 
-    fn right_click(&mut self, x: usize, y: usize) {
+    fn do_right_click(&mut self, x: usize, y: usize) {
         if self.state.is_end() {
             return;
         }
@@ -440,19 +607,20 @@ impl Board for StandardBoard {
             return;
         };
         self.init(None);
-        let Some(cell_state) = self.cell_states.get_mut(x, y) else {
-            return;
-        };
-        if *cell_state == CellState::Closed {
-            *cell_state = CellState::Flagged;
-            self.state.flag_added();
-        } else if *cell_state == CellState::Flagged {
-            *cell_state = CellState::Closed;
-            self.state.flag_removed();
+        match self.cell_states[(x, y)] {
+            CellState::Closed => {
+                self.set_cell_state(x, y, CellState::Flagged);
+                self.state.flag_added();
+            },
+            CellState::Flagged => {
+                self.set_cell_state(x, y, CellState::Closed);
+                self.state.flag_removed();
+            },
+            _ => {},
         }
     }
 
-    fn chord_click(&mut self, x: usize, y: usize, is_left: bool) -> bool {
+    fn do_chord_click(&mut self, x: usize, y: usize, is_left: bool) -> bool {
         if self.state.is_end() {
             return self.state.is_end();
         }
@@ -479,14 +647,12 @@ impl Board for StandardBoard {
                 }
             }
             if flagged_count == n {
+                let mut cells = Vec::with_capacity(8);
                 for dy in [-1isize, 0, 1] {
                     for dx in [-1isize, 0, 1] {
                         if dx == 0 && dy == 0 {
                             continue;
                         }
-                        if self.state.is_end() {
-                            break;
-                        }
                         let nx = x as isize + dx;
                         let ny = y as isize + dy;
                         if nx >= 0
@@ -494,16 +660,226 @@ impl Board for StandardBoard {
                             && let Some(cell_state) = self.cell_states.get(nx as usize, ny as usize)
                             && cell_state != &CellState::Flagged
                         {
-                            self.open(nx as usize, ny as usize);
+                            cells.push((nx as usize, ny as usize));
                         }
                     }
                 }
+                self.open(&cells);
+                self.state.check_win(self.cell_contents.len(), self.mines);
             }
         }
 
         self.state.is_end()
     }
 
+    fn do_resume(&mut self) {
+        if let BoardState::Lost {
+            opened_cells,
+            flags,
+            blasted_cell: (x, y),
+        } = self.state
+        {
+            info!("Resuming game after loss");
+            self.set_cell_state(x, y, CellState::Closed);
+            self.state = BoardState::InProgress { opened_cells, flags };
+        }
+    }
+
+    pub fn new(
+        mut width: usize,
+        mut height: usize,
+        mines: usize,
+        chord_mode: ChordMode,
+        first_click_policy: FirstClickPolicy,
+    ) -> Self {
+        width = width.max(1);
+        height = height.max(1);
+        Self {
+            cell_contents: Vec2D::new(width, height),
+            mines: mines.clamp(1, width * height),
+            chord_mode,
+            first_click_policy,
+            state: BoardState::NotStarted,
+            cell_states: Vec2D::new(width, height),
+            start_position: None,
+            seed: Some(rng().random()),
+            board_seed: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Start a game on an imported layout.
+    ///
+    /// If the pack carries cell states, the game continues from them, and the board state follows
+    /// from the opened, flagged and blasted cells. Returns `None` if they do not fit the layout.
+    pub fn import(pack: ImportPack, chord_mode: ChordMode) -> Option<Self> {
+        let ImportPack {
+            cell_contents,
+            mines,
+            start_position,
+            cell_states,
+            seed,
+        } = pack;
+        let (width, height) = cell_contents.dims();
+        let start_position = start_position.filter(|&(x, y)| x < width && y < height);
+        let mut state = BoardState::InProgress {
+            opened_cells: 0,
+            flags: 0,
+        };
+        let cell_states = match cell_states {
+            Some(cell_states) => {
+                state = Self::position_state(&cell_contents, &cell_states, mines)?;
+                cell_states
+            },
+            None => Vec2D::new(width, height),
+        };
+        Some(Self {
+            cell_contents,
+            mines,
+            chord_mode,
+            first_click_policy: FirstClickPolicy::default(),
+            state,
+            cell_states,
+            start_position,
+            seed: seed.map(|seed| seed.seed),
+            board_seed: seed,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            pending: None,
+        })
+    }
+
+    /// The state of a game whose cells are in `cell_states`, `None` if they do not fit the layout.
+    fn position_state(
+        cell_contents: &Vec2D<CellContent>,
+        cell_states: &Vec2D<CellState>,
+        mines: usize,
+    ) -> Option<BoardState> {
+        if cell_states.dims() != cell_contents.dims() {
+            error!(
+                "Cell states are {:?} but the layout is {:?}",
+                cell_states.dims(),
+                cell_contents.dims()
+            );
+            return None;
+        }
+
+        let width = cell_contents.dims().0;
+        let mut opened_cells = 0;
+        let mut flags = 0;
+        let mut blasted_cell = None;
+        for (index, (content, cell_state)) in cell_contents.iter().zip(cell_states.iter()).enumerate() {
+            match (content, cell_state) {
+                (_, CellState::Closed) => {},
+                (_, CellState::Flagged) => flags += 1,
+                (CellContent::Mine, CellState::Blasted) if blasted_cell.is_none() => {
+                    blasted_cell = Some((index % width, index / width));
+                },
+                (CellContent::Empty, CellState::Opening(0)) => opened_cells += 1,
+                (CellContent::Number(n), CellState::Opening(m)) if n == m => opened_cells += 1,
+                _ => {
+                    error!(
+                        "Cell state {:?} does not fit cell content {:?} at ({}, {})",
+                        cell_state,
+                        content,
+                        index % width,
+                        index / width
+                    );
+                    return None;
+                },
+            }
+        }
+
+        Some(match blasted_cell {
+            Some(blasted_cell) => BoardState::Lost {
+                opened_cells,
+                flags,
+                blasted_cell,
+            },
+            None if opened_cells + mines == cell_contents.len() => BoardState::Won,
+            None => BoardState::InProgress { opened_cells, flags },
+        })
+    }
+
+    /// Continue a started game, whose cell states must be in `pack`.
+    ///
+    /// Returns `None` if the cell states do not fit the layout of `pack` or disagree with `state`.
+    pub fn restore(pack: ImportPack, state: BoardState, chord_mode: ChordMode) -> Option<Self> {
+        if pack.cell_states.is_none() {
+            error!("No cell states to restore the game from");
+            return None;
+        }
+        let board = Self::import(pack, chord_mode)?;
+        if board.state != state {
+            error!(
+                "Board state {:?} does not match the cells, expected {:?}",
+                state, board.state
+            );
+            return None;
+        }
+        Some(board)
+    }
+}
+
+impl Board for StandardBoard {
+    fn width(&self) -> usize {
+        self.cell_contents.dims().0
+    }
+
+    fn height(&self) -> usize {
+        self.cell_contents.dims().1
+    }
+
+    fn mines(&self) -> usize {
+        self.mines
+    }
+
+    fn start_position(&self) -> Option<(usize, usize)> {
+        self.start_position
+    }
+
+    fn state(&self) -> BoardState {
+        self.state
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn board_seed(&self) -> Option<BoardSeed> {
+        self.board_seed
+    }
+
+    fn set_chord_mode(&mut self, mode: ChordMode) {
+        self.chord_mode = mode;
+    }
+
+    fn chord_mode(&self) -> ChordMode {
+        self.chord_mode
+    }
+
+    fn set_first_click_policy(&mut self, policy: FirstClickPolicy) {
+        self.first_click_policy = policy;
+    }
+
+    fn first_click_policy(&self) -> FirstClickPolicy {
+        self.first_click_policy
+    }
+
+    fn left_click(&mut self, x: usize, y: usize) -> bool {
+        self.record(|board| board.do_left_click(x, y))
+    }
+
+    fn right_click(&mut self, x: usize, y: usize) {
+        self.record(|board| board.do_right_click(x, y))
+    }
+
+    fn chord_click(&mut self, x: usize, y: usize, is_left: bool) -> bool {
+        self.record(|board| board.do_chord_click(x, y, is_left))
+    }
+
     fn cell_state(&self, x: usize, y: usize) -> Option<CellState> {
         self.cell_states.get(x, y).cloned()
     }
@@ -525,6 +901,11 @@ impl Board for StandardBoard {
         self.state = BoardState::NotStarted;
         self.cell_states.fill(CellState::Closed);
         self.cell_contents.fill(CellContent::Empty);
+        self.start_position = None;
+        self.seed = Some(rng().random());
+        self.board_seed = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
     }
 
     fn replay(&mut self) {
@@ -534,20 +915,179 @@ impl Board for StandardBoard {
             opened_cells: 0,
             flags: 0,
         };
+        self.undo_stack.clear();
+        self.redo_stack.clear();
     }
 
     fn resume(&mut self) {
-        if let BoardState::Lost {
-            opened_cells,
-            flags,
-            blasted_cell: (x, y),
-        } = self.state
-        {
-            info!("Resuming game after loss");
-            if let Some(cell_state) = self.cell_states.get_mut(x, y) {
-                *cell_state = CellState::Closed;
-            }
-            self.state = BoardState::InProgress { opened_cells, flags };
+        self.record(|board| board.do_resume())
+    }
+
+    fn undo(&mut self) -> bool {
+        let Some(delta) = self.undo_stack.pop() else {
+            return false;
+        };
+        for &((x, y), before, _) in delta.cells.iter().rev() {
+            self.cell_states[(x, y)] = before;
+        }
+        self.state = match delta.state_before {
+            // The mines stay where the first click placed them
+            BoardState::NotStarted => BoardState::InProgress {
+                opened_cells: 0,
+                flags: 0,
+            },
+            state => state,
+        };
+        debug!("Undid an action changing {} cells", delta.cells.len());
+        self.redo_stack.push(delta);
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some(delta) = self.redo_stack.pop() else {
+            return false;
+        };
+        for &((x, y), _, after) in &delta.cells {
+            self.cell_states[(x, y)] = after;
         }
+        self.state = delta.state_after;
+        debug!("Redid an action changing {} cells", delta.cells.len());
+        self.undo_stack.push(delta);
+        true
+    }
+
+    fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_large_sparse_board() {
+        let mut cell_contents = Vec2D::new(1000, 1000);
+        cell_contents[(999, 999)] = CellContent::Mine;
+        build_numbers(&mut cell_contents, 1);
+        let pack = ImportPack {
+            cell_contents,
+            mines: 1,
+            start_position: None,
+            cell_states: None,
+            seed: None,
+        };
+        let mut board = StandardBoard::import(pack, ChordMode::Standard).unwrap();
+        board.left_click(0, 0);
+        assert_eq!(board.state(), BoardState::Won);
+    }
+
+    /// A seed must give the same layout on every machine and every version.
+    #[test]
+    fn seed_layout_is_stable() {
+        let board_seed = BoardSeed {
+            width: 8,
+            height: 8,
+            mines: 10,
+            seed: 0x5eed,
+            first_click: Some((0, 0)),
+            policy: FirstClickPolicy::SafeCell,
+        };
+        let layout = board_seed.layout();
+        let mines: Vec<_> = (0..8)
+            .flat_map(|y| (0..8).map(move |x| (x, y)))
+            .filter(|&pos| layout[pos] == CellContent::Mine)
+            .collect();
+        assert_eq!(
+            mines,
+            [
+                (4, 0),
+                (1, 1),
+                (1, 4),
+                (6, 4),
+                (1, 5),
+                (6, 5),
+                (7, 5),
+                (2, 6),
+                (5, 6),
+                (5, 7)
+            ]
+        );
+    }
+
+    /// A 4x3 board with a single mine in the top left corner.
+    fn corner_mine_board() -> StandardBoard {
+        let pack = crate::base::encode_decode::ascii::decode("X...\n....\n....").unwrap();
+        StandardBoard::import(pack, ChordMode::Standard).unwrap()
+    }
+
+    fn in_progress(opened_cells: usize, flags: usize) -> BoardState {
+        BoardState::InProgress { opened_cells, flags }
+    }
+
+    #[test]
+    fn undo_redo_round_trip() {
+        let mut board = corner_mine_board();
+        board.left_click(1, 1);
+        assert_eq!(board.state(), in_progress(1, 0));
+        board.right_click(0, 0);
+        assert_eq!(board.state(), in_progress(1, 1));
+        board.chord_click(1, 1, true);
+        assert_eq!(board.state(), BoardState::Won);
+        let won = board.cell_states().clone();
+
+        assert!(board.undo());
+        assert_eq!(board.state(), in_progress(1, 1));
+        assert_eq!(board.cell_state(3, 2), Some(CellState::Closed));
+        assert!(board.undo());
+        assert_eq!(board.state(), in_progress(1, 0));
+        assert_eq!(board.cell_state(0, 0), Some(CellState::Closed));
+        assert!(board.undo());
+        assert_eq!(board.state(), in_progress(0, 0));
+        assert!(board.cell_states().iter().all(|&state| state == CellState::Closed));
+        assert!(!board.undo());
+
+        assert!(board.redo());
+        assert_eq!(board.state(), in_progress(1, 0));
+        assert!(board.redo());
+        assert_eq!(board.state(), in_progress(1, 1));
+        assert!(board.redo());
+        assert_eq!(board.state(), BoardState::Won);
+        assert_eq!(board.cell_states(), &won);
+        assert!(!board.redo());
+    }
+
+    #[test]
+    fn new_action_clears_redo() {
+        let mut board = corner_mine_board();
+        board.left_click(1, 1);
+        board.right_click(0, 0);
+        assert!(board.undo());
+        assert!(board.can_redo());
+        board.right_click(3, 2);
+        assert!(!board.can_redo());
+        assert!(!board.redo());
+        assert_eq!(board.state(), in_progress(1, 1));
+        assert_eq!(board.cell_state(0, 0), Some(CellState::Closed));
+        assert_eq!(board.cell_state(3, 2), Some(CellState::Flagged));
+    }
+
+    #[test]
+    fn undo_first_click_keeps_mines() {
+        let mut board = StandardBoard::new(4, 3, 2, ChordMode::Standard, FirstClickPolicy::SafeCell);
+        board.left_click(2, 1);
+        let after = board.state();
+        let layout = board.cell_contents().clone();
+        assert!(board.undo());
+        // The game stays started so that the first click does not place the mines again
+        assert_eq!(board.state(), in_progress(0, 0));
+        assert!(board.cell_states().iter().all(|&state| state == CellState::Closed));
+        assert!(board.redo());
+        assert_eq!(board.state(), after);
+        assert_eq!(board.cell_contents(), &layout);
     }
 }
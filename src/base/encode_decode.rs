@@ -1,27 +1,76 @@
+use crate::base::{Vec2D, board::ImportPack};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EncodeType {
     Ascii,
     AsciiWithNumbers,
+    AsciiWithStates,
     Base64,
     PttUrl,
     LlamaUrl,
+    Seed,
 }
 
 impl EncodeType {
-    pub const ENCODE_TYPES: [EncodeType; 5] = [
+    pub const ENCODE_TYPES: [EncodeType; 7] = [
         EncodeType::Ascii,
         EncodeType::AsciiWithNumbers,
+        EncodeType::AsciiWithStates,
         EncodeType::Base64,
         EncodeType::PttUrl,
         EncodeType::LlamaUrl,
+        EncodeType::Seed,
     ];
 
-    pub const DECODE_TYPES: [EncodeType; 4] = [
+    pub const DECODE_TYPES: [EncodeType; 6] = [
         EncodeType::Ascii,
+        EncodeType::AsciiWithStates,
         EncodeType::Base64,
         EncodeType::PttUrl,
         EncodeType::LlamaUrl,
+        EncodeType::Seed,
     ];
+
+    /// Encode a board in this format. Only [`EncodeType::AsciiWithStates`] keeps the cell states.
+    ///
+    /// Returns `None` for [`EncodeType::Seed`] if the layout was not generated from a seed.
+    pub fn encode(self, pack: &ImportPack) -> Option<String> {
+        let ImportPack {
+            cell_contents,
+            start_position,
+            cell_states,
+            seed,
+            ..
+        } = pack;
+        Some(match self {
+            EncodeType::Ascii => ascii::encode(cell_contents, *start_position),
+            EncodeType::AsciiWithNumbers => ascii::encode_with_numbers(cell_contents, *start_position),
+            EncodeType::AsciiWithStates => match cell_states {
+                Some(cell_states) => ascii::encode_with_states(cell_contents, cell_states, *start_position),
+                // Nothing opened yet
+                None => ascii::encode_with_states(
+                    cell_contents,
+                    &Vec2D::new(cell_contents.dims().0, cell_contents.dims().1),
+                    *start_position,
+                ),
+            },
+            EncodeType::Base64 => base64::encode(cell_contents, *start_position),
+            EncodeType::PttUrl => ptt_url::encode(cell_contents),
+            EncodeType::LlamaUrl => llama_url::encode(cell_contents),
+            EncodeType::Seed => self::seed::encode(seed.as_ref()?),
+        })
+    }
+
+    pub fn decode(self, text: &str) -> Option<ImportPack> {
+        match self {
+            EncodeType::Ascii | EncodeType::AsciiWithNumbers => ascii::decode(text),
+            EncodeType::AsciiWithStates => ascii::decode_with_states(text),
+            EncodeType::Base64 => base64::decode(text),
+            EncodeType::PttUrl => ptt_url::decode(text),
+            EncodeType::LlamaUrl => llama_url::decode(text),
+            EncodeType::Seed => seed::decode(text),
+        }
+    }
 }
 
 impl std::fmt::Display for EncodeType {
@@ -29,9 +78,11 @@ impl std::fmt::Display for EncodeType {
         match self {
             EncodeType::Ascii => write!(f, "ASCII"),
             EncodeType::AsciiWithNumbers => write!(f, "ASCII (with Numbers)"),
+            EncodeType::AsciiWithStates => write!(f, "ASCII (with States)"),
             EncodeType::Base64 => write!(f, "Base64"),
             EncodeType::PttUrl => write!(f, "PTT URL"),
             EncodeType::LlamaUrl => write!(f, "Llama URL"),
+            EncodeType::Seed => write!(f, "Seed"),
         }
     }
 }
@@ -40,6 +91,9 @@ pub mod ascii {
     use crate::base::{board::*, *};
     use log::error;
 
+    /// How many backtracking steps [`place_unknown_mines`] may take before giving up.
+    const MAX_PLACEMENT_STEPS: usize = 1_000_000;
+
     pub fn decode(ascii: &str) -> Option<ImportPack> {
         let lines: Vec<&str> = ascii.lines().collect();
         let height = lines.len();
@@ -89,6 +143,8 @@ pub mod ascii {
             cell_contents,
             mines,
             start_position,
+            cell_states: None,
+            seed: None,
         })
     }
 
@@ -144,6 +200,282 @@ pub mod ascii {
 
         ascii
     }
+
+    /// Decode a partially played position, which also tells which cells are opened and flagged:
+    ///
+    /// - `#` is a closed cell, `X`, `x` or `*` a closed mine and `?` a closed cell that may or may
+    ///   not be a mine;
+    /// - `@` is a closed cell where the game is meant to start;
+    /// - `F` is a flagged mine, and `f` a wrongly flagged cell;
+    /// - `0` to `8` are opened cells and `!` is a blasted mine.
+    ///
+    /// The position may start with a `mines: <count>` line, which is required when it has `?`
+    /// cells, e.g. one copied from a forum post. Mines are then placed among the `?` cells so that
+    /// every opened number fits, see [`place_unknown_mines`]. The layout is picked at random, so
+    /// the same text imported twice may give different games.
+    pub fn decode_with_states(ascii: &str) -> Option<ImportPack> {
+        let mut lines: Vec<&str> = ascii.lines().collect();
+        let mut total_mines = None;
+        if let Some(count) = lines.first().and_then(|line| line.trim().strip_prefix("mines:")) {
+            let Ok(count) = count.trim().parse::<usize>() else {
+                error!("Invalid mine count in ASCII input: {}", count);
+                return None;
+            };
+            total_mines = Some(count);
+            lines.remove(0);
+        }
+        let height = lines.len();
+        if height == 0 {
+            error!("ASCII input has no lines");
+            return None;
+        }
+        let width = lines[0].chars().count();
+        if width == 0 {
+            error!("ASCII input has no width");
+            return None;
+        }
+
+        let mut cell_contents = Vec2D::new(width, height);
+        let mut cell_states = Vec2D::new(width, height);
+        let mut mines = 0;
+        let mut start_position = None;
+        let mut unknown = Vec::new();
+
+        for (y, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                error!("Inconsistent line width in ASCII input");
+                return None;
+            }
+            for (x, c) in line.chars().enumerate() {
+                let (is_mine, state) = match c {
+                    '#' => (false, CellState::Closed),
+                    '*' | 'x' | 'X' => (true, CellState::Closed),
+                    '?' => {
+                        unknown.push((x, y));
+                        (false, CellState::Closed)
+                    },
+                    '@' => {
+                        start_position = Some((x, y));
+                        (false, CellState::Closed)
+                    },
+                    'F' => (true, CellState::Flagged),
+                    'f' => (false, CellState::Flagged),
+                    '0'..='8' => (false, CellState::Opening(c as u8 - b'0')),
+                    '!' => (true, CellState::Blasted),
+                    _ => {
+                        error!("Invalid character in ASCII input: {}", c);
+                        return None;
+                    },
+                };
+                if is_mine {
+                    cell_contents[(x, y)] = CellContent::Mine;
+                    mines += 1;
+                }
+                cell_states[(x, y)] = state;
+            }
+        }
+
+        match total_mines {
+            Some(total_mines) if total_mines < mines => {
+                error!(
+                    "Mine count {} is less than the {} mines in ASCII input",
+                    total_mines, mines
+                );
+                return None;
+            },
+            Some(total_mines) if total_mines > mines && unknown.is_empty() => {
+                error!("Mine count {} but only {} mines in ASCII input", total_mines, mines);
+                return None;
+            },
+            Some(total_mines) => {
+                if let Err(reason) =
+                    place_unknown_mines(&mut cell_contents, &cell_states, &unknown, total_mines - mines)
+                {
+                    error!("Cannot place {} mines in ASCII input: {}", total_mines, reason);
+                    return None;
+                }
+                mines = total_mines;
+            },
+            None if !unknown.is_empty() => {
+                error!("ASCII input with unknown cells needs a mine count");
+                return None;
+            },
+            None => {},
+        }
+
+        build_numbers(&mut cell_contents, mines);
+
+        Some(ImportPack {
+            cell_contents,
+            mines,
+            start_position,
+            cell_states: Some(cell_states),
+            seed: None,
+        })
+    }
+
+    /// Place `mines` mines among the `unknown` closed cells so that every opened number sees as
+    /// many mines as it shows.
+    ///
+    /// Unknown cells next to an opened number are assigned by backtracking, with an explicit stack
+    /// so that large positions cannot overflow it; the mines left over are spread randomly over the
+    /// other unknown cells. Any fitting layout will do, since the solver only looks at the cells
+    /// the player can see. The search gives up after [`MAX_PLACEMENT_STEPS`] steps, as a large
+    /// frontier may take exponential time.
+    fn place_unknown_mines(
+        cell_contents: &mut Vec2D<CellContent>,
+        cell_states: &Vec2D<CellState>,
+        unknown: &[(usize, usize)],
+        mines: usize,
+    ) -> Result<(), &'static str> {
+        const NO_LAYOUT: &str = "no layout fits the numbers";
+        let (width, height) = cell_contents.dims();
+        let mut is_unknown = Vec2D::filled(width, height, false);
+        for &pos in unknown {
+            is_unknown[pos] = true;
+        }
+
+        // For each opened number, the mines still to be placed and the unknown cells around it
+        let mut needed = Vec::new();
+        let mut unassigned = Vec::new();
+        let mut witnesses = Vec2D::filled(width, height, Vec::new());
+        for y in 0..height {
+            for x in 0..width {
+                let CellState::Opening(n) = cell_states[(x, y)] else {
+                    continue;
+                };
+                let mut known_mines = 0;
+                let mut around = Vec::new();
+                for pos in statistics::neighbors(width, height, x, y) {
+                    if is_unknown[pos] {
+                        around.push(pos);
+                    } else if cell_contents[pos] == CellContent::Mine {
+                        known_mines += 1;
+                    }
+                }
+                let Some(missing) = (n as usize).checked_sub(known_mines).filter(|&m| m <= around.len()) else {
+                    return Err(NO_LAYOUT);
+                };
+                if around.is_empty() {
+                    continue;
+                }
+                for &pos in &around {
+                    witnesses[pos].push(needed.len());
+                }
+                needed.push(missing);
+                unassigned.push(around.len());
+            }
+        }
+        let (frontier, wilderness): (Vec<_>, Vec<_>) =
+            unknown.iter().copied().partition(|&pos| !witnesses[pos].is_empty());
+
+        let fits = |needed: &[usize], unassigned: &[usize], pos: (usize, usize), is_mine: bool| {
+            witnesses[pos].iter().all(|&w| {
+                let unassigned = unassigned[w] - 1;
+                if is_mine {
+                    needed[w] >= 1 && needed[w] - 1 <= unassigned
+                } else {
+                    needed[w] <= unassigned
+                }
+            })
+        };
+        let mut assignment: Vec<Option<bool>> = vec![None; frontier.len()];
+        let mut placed = 0;
+        let mut index = 0;
+        let mut steps = 0;
+        loop {
+            steps += 1;
+            if steps > MAX_PLACEMENT_STEPS {
+                return Err("too many layouts to search, reveal more cells");
+            }
+            if index == frontier.len() {
+                if placed <= mines && mines - placed <= wilderness.len() {
+                    break;
+                } else if index == 0 {
+                    return Err(NO_LAYOUT);
+                }
+                index -= 1;
+                continue;
+            }
+
+            // Take back the value tried last, then try the next one that fits
+            let pos = frontier[index];
+            let mut value = match assignment[index] {
+                None => Some(false),
+                Some(is_mine) => {
+                    for &w in &witnesses[pos] {
+                        unassigned[w] += 1;
+                        needed[w] += is_mine as usize;
+                    }
+                    placed -= is_mine as usize;
+                    (!is_mine).then_some(true)
+                },
+            };
+            while let Some(is_mine) = value {
+                if (!is_mine || placed < mines) && fits(&needed, &unassigned, pos, is_mine) {
+                    break;
+                }
+                value = (!is_mine).then_some(true);
+            }
+
+            assignment[index] = value;
+            match value {
+                Some(is_mine) => {
+                    for &w in &witnesses[pos] {
+                        unassigned[w] -= 1;
+                        needed[w] -= is_mine as usize;
+                    }
+                    placed += is_mine as usize;
+                    index += 1;
+                },
+                None if index == 0 => return Err(NO_LAYOUT),
+                None => index -= 1,
+            }
+        }
+
+        for (&pos, is_mine) in frontier.iter().zip(assignment) {
+            if is_mine == Some(true) {
+                cell_contents[pos] = CellContent::Mine;
+            }
+        }
+        for index in rand::seq::index::sample(&mut rand::rng(), wilderness.len(), mines - placed) {
+            cell_contents[wilderness[index]] = CellContent::Mine;
+        }
+        Ok(())
+    }
+
+    /// Encode a position in the dialect read by [`decode_with_states`].
+    ///
+    /// The start position is only kept while it is still closed.
+    pub fn encode_with_states(
+        cell_contents: &Vec2D<CellContent>,
+        cell_states: &Vec2D<CellState>,
+        start_position: Option<(usize, usize)>,
+    ) -> String {
+        let (width, height) = cell_contents.dims();
+        let mut ascii = String::with_capacity(width * height + height - 1);
+
+        for y in 0..height {
+            for x in 0..width {
+                let is_mine = cell_contents[(x, y)] == CellContent::Mine;
+                let c = match cell_states[(x, y)] {
+                    CellState::Closed if is_mine => 'X',
+                    CellState::Closed if start_position == Some((x, y)) => '@',
+                    CellState::Closed => '#',
+                    CellState::Flagged if is_mine => 'F',
+                    CellState::Flagged => 'f',
+                    CellState::Opening(n) => char::from_digit(n as u32, 10).unwrap(),
+                    CellState::Blasted => '!',
+                };
+                ascii.push(c);
+            }
+            if y < height - 1 {
+                ascii.push('\n');
+            }
+        }
+
+        ascii
+    }
 }
 
 pub mod base64 {
@@ -229,6 +561,8 @@ pub mod base64 {
             cell_contents,
             mines,
             start_position,
+            cell_states: None,
+            seed: None,
         })
     }
 
@@ -394,6 +728,8 @@ mod base32hex {
             cell_contents,
             mines,
             start_position: None,
+            cell_states: None,
+            seed: None,
         })
     }
 
@@ -540,3 +876,150 @@ pub mod llama_url {
         )
     }
 }
+
+/// The recipe of a generated board instead of its layout, e.g. `30x16:99:5f0e3c9a1d2b7486:4,7:safe-cell`.
+///
+/// Fields are the size, the mines, the seed in hexadecimal, the first click (`-` if there was
+/// none) and the first click policy. See [`BoardSeed::layout`].
+pub mod seed {
+    use crate::base::board::*;
+    use log::error;
+
+    /// The largest board a seed may describe, as in the board config.
+    const MAX_SIZE: usize = 1000;
+
+    fn policy_name(policy: FirstClickPolicy) -> &'static str {
+        match policy {
+            FirstClickPolicy::None => "none",
+            FirstClickPolicy::SafeCell => "safe-cell",
+            FirstClickPolicy::Opening => "opening",
+            FirstClickPolicy::SafeRow => "safe-row",
+        }
+    }
+
+    pub fn decode(text: &str) -> Option<ImportPack> {
+        parse(text).map(|board_seed| board_seed.import_pack())
+    }
+
+    /// Read the seed without generating its layout.
+    pub fn parse(text: &str) -> Option<BoardSeed> {
+        let fields: Vec<&str> = text.trim().split(':').collect();
+        let &[size, mines, seed, first_click, policy] = fields.as_slice() else {
+            error!("Seed must have 5 fields separated by ':', got {}", fields.len());
+            return None;
+        };
+
+        let Some((width, height)) = size
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?)))
+        else {
+            error!("Invalid board size in seed: {}", size);
+            return None;
+        };
+        if !(1..=MAX_SIZE).contains(&width) || !(1..=MAX_SIZE).contains(&height) {
+            error!("Invalid board dimensions: {}x{}", width, height);
+            return None;
+        }
+
+        let Some(mines) = mines
+            .parse::<usize>()
+            .ok()
+            .filter(|&mines| (1..=width * height).contains(&mines))
+        else {
+            error!("Invalid mine count in seed: {}", mines);
+            return None;
+        };
+
+        let Ok(seed) = u64::from_str_radix(seed, 16) else {
+            error!("Invalid seed: {}", seed);
+            return None;
+        };
+
+        let first_click = match first_click {
+            "-" => None,
+            position => {
+                let Some((x, y)) = position
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse::<usize>().ok()?, y.parse::<usize>().ok()?)))
+                    .filter(|&(x, y)| x < width && y < height)
+                else {
+                    error!("Invalid first click in seed: {}", position);
+                    return None;
+                };
+                Some((x, y))
+            },
+        };
+
+        let Some(policy) = FirstClickPolicy::ALL
+            .into_iter()
+            .find(|&candidate| policy_name(candidate) == policy)
+        else {
+            error!("Unknown first click policy in seed: {}", policy);
+            return None;
+        };
+
+        Some(BoardSeed {
+            width,
+            height,
+            mines,
+            seed,
+            first_click,
+            policy,
+        })
+    }
+
+    pub fn encode(board_seed: &BoardSeed) -> String {
+        let first_click = match board_seed.first_click {
+            Some((x, y)) => format!("{},{}", x, y),
+            None => "-".to_string(),
+        };
+        format!(
+            "{}x{}:{}:{:016x}:{}:{}",
+            board_seed.width,
+            board_seed.height,
+            board_seed.mines,
+            board_seed.seed,
+            first_click,
+            policy_name(board_seed.policy)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::board::{Board, BoardState, CellContent, ChordMode, StandardBoard};
+
+    #[test]
+    fn ascii_with_unknown_cells() {
+        let pack = ascii::decode_with_states("mines: 10\n??????\n?2????\n?1F???\n011???\n000???").unwrap();
+        assert_eq!(pack.mines, 10);
+        assert_eq!(
+            pack.cell_contents.iter().filter(|&&c| c == CellContent::Mine).count(),
+            10
+        );
+        let board = StandardBoard::import(pack, ChordMode::Standard).unwrap();
+        assert!(matches!(board.state(), BoardState::InProgress { .. }));
+
+        assert!(ascii::decode_with_states("??\n?1").is_none());
+        assert!(ascii::decode_with_states("mines: 3\n??\n?1").is_none());
+    }
+
+    #[test]
+    fn ascii_with_unknown_cells_gives_up() {
+        // Every `1` holds one mine, so 24 mines cannot fit, but proving it takes 2^24 steps
+        let text = format!("mines: 24\n{}", "?1?\n###\n".repeat(25));
+        assert!(ascii::decode_with_states(&text).is_none());
+        assert!(ascii::decode_with_states(&text.replacen("24", "25", 1)).is_some());
+    }
+
+    #[test]
+    fn ascii_with_states_round_trip() {
+        let text = "#X#\n@1#\n000";
+        let pack = ascii::decode_with_states(text).unwrap();
+        assert_eq!(pack.start_position, Some((0, 1)));
+        let encoded = EncodeType::AsciiWithStates.encode(&pack).unwrap();
+        assert_eq!(encoded, text);
+        assert!(StandardBoard::import(pack, ChordMode::Standard).is_some());
+    }
+}
@@ -0,0 +1,284 @@
+use super::{
+    Vec2D,
+    board::{Board, BoardState, CellContent, CellState},
+};
+use std::{collections::VecDeque, time::Duration};
+
+/// The in-bounds neighbours of `(x, y)` on a `width` x `height` board.
+pub(super) fn neighbors(
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> impl Iterator<Item = (usize, usize)> + use<> {
+    (x.saturating_sub(1)..=(x + 1).min(width - 1))
+        .flat_map(move |nx| (y.saturating_sub(1)..=(y + 1).min(height - 1)).map(move |ny| (nx, ny)))
+        .filter(move |&(nx, ny)| nx != x || ny != y)
+}
+
+/// The 3BV unit a cell belongs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum Unit {
+    /// A mine, or a number on the border of an opening, which costs no click of its own.
+    #[default]
+    None,
+    /// An empty cell of the opening with the given index.
+    Opening(usize),
+    /// A number cell that is not on the border of any opening.
+    Standalone,
+}
+
+/// Static difficulty metrics of a mine layout.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    units: Vec2D<Unit>,
+    bbbv: usize,
+    openings: usize,
+    islands: usize,
+}
+
+impl Layout {
+    /// Analyse a layout whose numbers have been built.
+    pub fn analyze(cell_contents: &Vec2D<CellContent>) -> Self {
+        let (width, height) = cell_contents.dims();
+        let neighbors = |x: usize, y: usize| neighbors(width, height, x, y);
+        let mut units = Vec2D::filled(width, height, Unit::None);
+        let mut queue = VecDeque::new();
+
+        // Every connected region of empty cells is one opening
+        let mut openings = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if cell_contents[(x, y)] != CellContent::Empty || units[(x, y)] != Unit::None {
+                    continue;
+                }
+                units[(x, y)] = Unit::Opening(openings);
+                queue.push_back((x, y));
+                while let Some((cx, cy)) = queue.pop_front() {
+                    for (nx, ny) in neighbors(cx, cy) {
+                        if cell_contents[(nx, ny)] == CellContent::Empty && units[(nx, ny)] == Unit::None {
+                            units[(nx, ny)] = Unit::Opening(openings);
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+                openings += 1;
+            }
+        }
+
+        // Numbers away from every opening need a click each
+        let mut standalone = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if matches!(cell_contents[(x, y)], CellContent::Number(_))
+                    && !neighbors(x, y).any(|pos| cell_contents[pos] == CellContent::Empty)
+                {
+                    units[(x, y)] = Unit::Standalone;
+                    standalone += 1;
+                }
+            }
+        }
+
+        // Islands are connected groups of standalone numbers
+        let mut islands = 0;
+        let mut visited = Vec2D::filled(width, height, false);
+        for y in 0..height {
+            for x in 0..width {
+                if units[(x, y)] != Unit::Standalone || visited[(x, y)] {
+                    continue;
+                }
+                visited[(x, y)] = true;
+                queue.push_back((x, y));
+                while let Some((cx, cy)) = queue.pop_front() {
+                    for pos in neighbors(cx, cy) {
+                        if units[pos] == Unit::Standalone && !visited[pos] {
+                            visited[pos] = true;
+                            queue.push_back(pos);
+                        }
+                    }
+                }
+                islands += 1;
+            }
+        }
+
+        Self {
+            units,
+            bbbv: openings + standalone,
+            openings,
+            islands,
+        }
+    }
+
+    pub(super) fn unit(&self, x: usize, y: usize) -> Unit {
+        self.units[(x, y)]
+    }
+
+    /// The minimum number of left clicks needed to solve the board without chording.
+    pub fn bbbv(&self) -> usize {
+        self.bbbv
+    }
+
+    pub fn openings(&self) -> usize {
+        self.openings
+    }
+
+    pub fn islands(&self) -> usize {
+        self.islands
+    }
+
+    /// Count the 3BV units already revealed in `cell_states`.
+    pub fn solved_bbbv(&self, cell_states: &Vec2D<CellState>) -> usize {
+        let mut opened = vec![false; self.openings];
+        let mut solved = 0;
+        for (unit, state) in self.units.iter().zip(cell_states.iter()) {
+            if !matches!(state, CellState::Opening(_)) {
+                continue;
+            }
+            match *unit {
+                Unit::Opening(index) if !opened[index] => {
+                    opened[index] = true;
+                    solved += 1;
+                },
+                Unit::Standalone => solved += 1,
+                _ => {},
+            }
+        }
+        solved
+    }
+}
+
+/// The kind of a click, as sent to the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Click {
+    Left,
+    Right,
+    Chord,
+}
+
+/// Live statistics of a game.
+///
+/// The layout is only known once the mines have been placed, i.e. after the first click on a
+/// fresh board, so its metrics are `None` until then. The ZiNi of the layout takes a while on large
+/// boards, so it is computed elsewhere once the game ends and set with [`Statistics::set_zini`].
+#[derive(Debug, Clone, Default)]
+pub struct Statistics {
+    layout: Option<Layout>,
+    /// Clicks of the greedy and the human ZiNi solutions.
+    zini: Option<(usize, usize)>,
+    solved_bbbv: usize,
+    left_clicks: usize,
+    right_clicks: usize,
+    chord_clicks: usize,
+    wasted_clicks: usize,
+}
+
+impl Statistics {
+    /// Record a click that has just been applied to `board`.
+    ///
+    /// A click is wasted if it changed neither the board state nor any cell.
+    pub fn record(&mut self, click: Click, wasted: bool, board: &dyn Board) {
+        match click {
+            Click::Left => self.left_clicks += 1,
+            Click::Right => self.right_clicks += 1,
+            Click::Chord => self.chord_clicks += 1,
+        }
+        if wasted {
+            self.wasted_clicks += 1;
+        }
+        self.refresh(board);
+    }
+
+    /// Update the layout and the solved 3BV from `board`.
+    pub fn refresh(&mut self, board: &dyn Board) {
+        if board.state() == BoardState::NotStarted {
+            self.layout = None;
+            self.zini = None;
+            self.solved_bbbv = 0;
+            return;
+        }
+        let layout = self
+            .layout
+            .get_or_insert_with(|| Layout::analyze(board.cell_contents()));
+        self.solved_bbbv = layout.solved_bbbv(board.cell_states());
+    }
+
+    /// The layout whose ZiNi is still to be computed, if the game on `board` has ended.
+    pub fn pending_zini(&self, board: &dyn Board) -> Option<&Layout> {
+        self.layout
+            .as_ref()
+            .filter(|_| board.state().is_end() && self.zini.is_none())
+    }
+
+    /// Set the clicks of the greedy and the human ZiNi solutions of the layout.
+    pub fn set_zini(&mut self, greedy: usize, human: usize) {
+        if self.layout.is_some() {
+            self.zini = Some((greedy, human));
+        }
+    }
+
+    /// Clear the click counts, keeping the layout.
+    pub fn clear_clicks(&mut self) {
+        *self = Statistics {
+            layout: self.layout.take(),
+            zini: self.zini,
+            ..Default::default()
+        };
+    }
+
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_ref()
+    }
+
+    /// Number of clicks of the greedy ZiNi solution, see [`super::zini::greedy`].
+    pub fn zini(&self) -> Option<usize> {
+        self.zini.map(|(greedy, _)| greedy)
+    }
+
+    /// Number of clicks of the human ZiNi solution, see [`super::zini::human`].
+    pub fn human_zini(&self) -> Option<usize> {
+        self.zini.map(|(_, human)| human)
+    }
+
+    pub fn solved_bbbv(&self) -> usize {
+        self.solved_bbbv
+    }
+
+    pub fn left_clicks(&self) -> usize {
+        self.left_clicks
+    }
+
+    pub fn right_clicks(&self) -> usize {
+        self.right_clicks
+    }
+
+    pub fn chord_clicks(&self) -> usize {
+        self.chord_clicks
+    }
+
+    pub fn wasted_clicks(&self) -> usize {
+        self.wasted_clicks
+    }
+
+    pub fn clicks(&self) -> usize {
+        self.left_clicks + self.right_clicks + self.chord_clicks
+    }
+
+    pub fn effective_clicks(&self) -> usize {
+        self.clicks() - self.wasted_clicks
+    }
+
+    /// Index of efficiency: solved 3BV per click.
+    pub fn ioe(&self) -> f64 {
+        self.solved_bbbv as f64 / self.clicks() as f64
+    }
+
+    /// Solved 3BV per effective click.
+    pub fn throughput(&self) -> f64 {
+        self.solved_bbbv as f64 / self.effective_clicks() as f64
+    }
+
+    /// Solved 3BV per second of play.
+    pub fn bbbv_per_second(&self, elapsed: Duration) -> f64 {
+        self.solved_bbbv as f64 / elapsed.as_secs_f64()
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+/// A stopwatch measuring the time spent on a game.
+///
+/// The timer keeps full `Instant` precision; callers round it as needed for display.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timer {
+    /// Time accumulated before the current run.
+    accumulated: Duration,
+    /// When the current run started, if the timer is running.
+    running_since: Option<Instant>,
+}
+
+impl Timer {
+    /// Start the timer, continuing from where it stopped if it has not been reset.
+    pub fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Stop the timer, keeping the elapsed time.
+    pub fn stop(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.accumulated += since.elapsed();
+        }
+    }
+
+    /// Stop the timer and clear the elapsed time.
+    pub fn reset(&mut self) {
+        *self = Timer::default();
+    }
+
+    /// Stop the timer at `elapsed`.
+    pub fn set(&mut self, elapsed: Duration) {
+        *self = Timer {
+            accumulated: elapsed,
+            running_since: None,
+        };
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.accumulated + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
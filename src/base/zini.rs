@@ -0,0 +1,238 @@
+use super::{
+    Vec2D,
+    board::{CellContent, CellState},
+    statistics::{Click, Layout, Unit, neighbors},
+};
+use std::{cmp::Reverse, collections::BTreeSet};
+
+/// A single click of a solving sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZiniClick {
+    pub click: Click,
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Apply `click` to `cell_states` of a board with the given `cell_contents`, returning the cells
+/// whose state changed.
+///
+/// Mines are never opened: a sequence only ever clicks safe cells, and chords around numbers whose
+/// mines have all been flagged.
+pub fn apply(
+    cell_contents: &Vec2D<CellContent>,
+    cell_states: &mut Vec2D<CellState>,
+    click: ZiniClick,
+) -> Vec<(usize, usize)> {
+    let (width, height) = cell_contents.dims();
+    let mut changed = Vec::new();
+    match click.click {
+        Click::Left => open(cell_contents, cell_states, click.x, click.y, &mut changed),
+        Click::Right => {
+            let state = &mut cell_states[(click.x, click.y)];
+            match state {
+                CellState::Closed => *state = CellState::Flagged,
+                CellState::Flagged => *state = CellState::Closed,
+                _ => return changed,
+            }
+            changed.push((click.x, click.y));
+        },
+        Click::Chord => {
+            for (nx, ny) in neighbors(width, height, click.x, click.y) {
+                if cell_states[(nx, ny)] == CellState::Closed {
+                    open(cell_contents, cell_states, nx, ny, &mut changed);
+                }
+            }
+        },
+    }
+    changed
+}
+
+fn open(
+    cell_contents: &Vec2D<CellContent>,
+    cell_states: &mut Vec2D<CellState>,
+    x: usize,
+    y: usize,
+    changed: &mut Vec<(usize, usize)>,
+) {
+    let (width, height) = cell_contents.dims();
+    let mut stack = vec![(x, y)];
+    while let Some((x, y)) = stack.pop() {
+        if cell_states[(x, y)] != CellState::Closed {
+            continue;
+        }
+        let number = match cell_contents[(x, y)] {
+            CellContent::Empty => 0,
+            CellContent::Number(n) => n,
+            CellContent::Mine => continue,
+        };
+        cell_states[(x, y)] = CellState::Opening(number);
+        changed.push((x, y));
+        if number == 0 {
+            stack.extend(neighbors(width, height, x, y));
+        }
+    }
+}
+
+/// A board being solved by a known sequence of clicks.
+struct Simulation<'a> {
+    cell_contents: &'a Vec2D<CellContent>,
+    layout: &'a Layout,
+    cell_states: Vec2D<CellState>,
+    clicks: Vec<ZiniClick>,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(cell_contents: &'a Vec2D<CellContent>, layout: &'a Layout) -> Self {
+        let (width, height) = cell_contents.dims();
+        Self {
+            cell_contents,
+            layout,
+            cell_states: Vec2D::filled(width, height, CellState::Closed),
+            clicks: Vec::new(),
+        }
+    }
+
+    fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let (width, height) = self.cell_contents.dims();
+        neighbors(width, height, x, y)
+    }
+
+    fn click(&mut self, click: Click, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let click = ZiniClick { click, x, y };
+        self.clicks.push(click);
+        apply(self.cell_contents, &mut self.cell_states, click)
+    }
+
+    /// Whether `(x, y)` is a closed cell which still needs a click of its own.
+    fn is_unsolved(&self, x: usize, y: usize) -> bool {
+        self.cell_states[(x, y)] == CellState::Closed && self.layout.unit(x, y) != Unit::None
+    }
+
+    /// The 3BV solved by chording `(x, y)` minus the clicks it takes: flagging its mines, opening
+    /// it if it is closed and the chord itself. `None` if the chord would solve nothing.
+    fn premium(&self, x: usize, y: usize) -> Option<isize> {
+        let CellContent::Number(_) = self.cell_contents[(x, y)] else {
+            return None;
+        };
+        let closed = self.cell_states[(x, y)] == CellState::Closed;
+        let mut cost = 1 + closed as isize;
+        let mut gain = 0;
+        // At most 9 distinct openings can be touched
+        let mut openings = [usize::MAX; 9];
+        let mut touched = 0;
+        let mut solve = |unit| match unit {
+            Unit::Opening(index) if !openings.contains(&index) => {
+                openings[touched] = index;
+                touched += 1;
+                gain += 1;
+            },
+            Unit::Standalone => gain += 1,
+            _ => {},
+        };
+        if closed {
+            solve(self.layout.unit(x, y));
+        }
+        for (nx, ny) in self.neighbors(x, y) {
+            match (self.cell_contents[(nx, ny)], self.cell_states[(nx, ny)]) {
+                (CellContent::Mine, CellState::Closed) => cost += 1,
+                (_, CellState::Closed) => solve(self.layout.unit(nx, ny)),
+                _ => {},
+            }
+        }
+        (gain > 0).then_some(gain - cost)
+    }
+
+    /// Flag the mines around `(x, y)`, open it if needed and chord it.
+    fn chord(&mut self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut changed = Vec::new();
+        for (nx, ny) in self.neighbors(x, y) {
+            if self.cell_contents[(nx, ny)] == CellContent::Mine && self.cell_states[(nx, ny)] == CellState::Closed {
+                changed.extend(self.click(Click::Right, nx, ny));
+            }
+        }
+        if self.cell_states[(x, y)] == CellState::Closed {
+            changed.extend(self.click(Click::Left, x, y));
+        }
+        changed.extend(self.click(Click::Chord, x, y));
+        changed
+    }
+}
+
+/// Solve a layout with the greedy ZiNi algorithm.
+///
+/// The number with the highest positive premium is chorded, ties going to the first one in reading
+/// order, until no chord pays off; the first unsolved 3BV cell in reading order is then left
+/// clicked, and so on until the board is solved.
+pub fn greedy(cell_contents: &Vec2D<CellContent>, layout: &Layout) -> Vec<ZiniClick> {
+    let (width, height) = cell_contents.dims();
+    let mut simulation = Simulation::new(cell_contents, layout);
+    // Only chords that pay off are queued, ordered by premium, then in reading order
+    let mut premiums: Vec2D<Option<isize>> = Vec2D::filled(width, height, None);
+    let mut queue = BTreeSet::new();
+
+    let refresh = |simulation: &Simulation,
+                   premiums: &mut Vec2D<Option<isize>>,
+                   queue: &mut BTreeSet<(Reverse<isize>, usize, usize)>,
+                   (x, y): (usize, usize)| {
+        let premium = simulation.premium(x, y).filter(|&premium| premium > 0);
+        if premium == premiums[(x, y)] {
+            return;
+        }
+        if let Some(old) = premiums[(x, y)] {
+            queue.remove(&(Reverse(old), y, x));
+        }
+        if let Some(new) = premium {
+            queue.insert((Reverse(new), y, x));
+        }
+        premiums[(x, y)] = premium;
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            refresh(&simulation, &mut premiums, &mut queue, (x, y));
+        }
+    }
+
+    let mut cursor = 0;
+    loop {
+        let changed = match queue.first() {
+            Some(&(_, y, x)) => simulation.chord(x, y),
+            None => {
+                while cursor < width * height && !simulation.is_unsolved(cursor % width, cursor / width) {
+                    cursor += 1;
+                }
+                if cursor == width * height {
+                    break;
+                }
+                simulation.click(Click::Left, cursor % width, cursor / width)
+            },
+        };
+        for (x, y) in changed {
+            refresh(&simulation, &mut premiums, &mut queue, (x, y));
+            for pos in simulation.neighbors(x, y) {
+                refresh(&simulation, &mut premiums, &mut queue, pos);
+            }
+        }
+    }
+
+    simulation.clicks
+}
+
+/// Solve a layout the way a human sweeping the board from the top left would.
+///
+/// Every cell is visited once in reading order: it is chorded if its premium is positive, and left
+/// clicked if it is an unsolved 3BV cell.
+pub fn human(cell_contents: &Vec2D<CellContent>, layout: &Layout) -> Vec<ZiniClick> {
+    let (width, height) = cell_contents.dims();
+    let mut simulation = Simulation::new(cell_contents, layout);
+    for y in 0..height {
+        for x in 0..width {
+            if simulation.premium(x, y).is_some_and(|premium| premium > 0) {
+                simulation.chord(x, y);
+            } else if simulation.is_unsolved(x, y) {
+                simulation.click(Click::Left, x, y);
+            }
+        }
+    }
+    simulation.clicks
+}
@@ -1,15 +1,15 @@
-use crate::base::board;
+use crate::{base::board, key_binding::KeyBindings};
 use log::{debug, error, info};
 use pastey::paste;
 
 macro_rules! update_config {
     (
-        #[$meta:meta]
+        $(#[$meta:meta])*
         $vis:vis struct $name:ident {
             $($field_vis:vis $field_name:ident : $field_type:ty),* $(,)?
         }
     ) => {
-        #[$meta]
+        $(#[$meta])*
         $vis struct $name {
             $( $field_vis $field_name : $field_type ),*
         }
@@ -48,11 +48,77 @@ macro_rules! update_config {
 
 update_config! {
     #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    #[serde(default)]
     pub struct GlobalConfig {
         pub skin: String,
         pub cell_size: u32,
         pub board: [usize; 3], // width, height, mines
         pub chord_mode: board::ChordMode,
+        pub first_click_policy: board::FirstClickPolicy,
+        pub no_guess: bool,
+        pub presets: Vec<BoardPreset>, // custom ones, besides BoardPreset::BUILTIN
+        pub key_bindings: KeyBindings,
+    }
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        GlobalConfig {
+            chord_mode: board::ChordMode::LeftClick,
+            skin: "WoM Light".to_string(),
+            cell_size: 24,
+            board: [30, 16, 99],
+            first_click_policy: board::FirstClickPolicy::default(),
+            no_guess: false,
+            presets: Vec::new(),
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+/// A named board size.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BoardPreset {
+    pub name: String,
+    pub board: [usize; 3], // width, height, mines
+}
+
+impl BoardPreset {
+    pub const BUILTIN: [(&str, [usize; 3]); 3] = [
+        ("Beginner", [9, 9, 10]),
+        ("Intermediate", [16, 16, 40]),
+        ("Expert", [30, 16, 99]),
+    ];
+
+    pub fn builtin() -> impl Iterator<Item = BoardPreset> {
+        Self::BUILTIN.into_iter().map(|(name, board)| BoardPreset {
+            name: name.to_string(),
+            board,
+        })
+    }
+
+    pub fn is_builtin_name(name: &str) -> bool {
+        Self::BUILTIN.iter().any(|(builtin, _)| *builtin == name)
+    }
+
+    /// The name of the preset of `board`, built-in presets first and then `custom` ones.
+    pub fn name_of(board: [usize; 3], custom: &[BoardPreset]) -> Option<String> {
+        Self::BUILTIN
+            .iter()
+            .find(|(_, builtin)| *builtin == board)
+            .map(|(name, _)| name.to_string())
+            .or_else(|| {
+                custom
+                    .iter()
+                    .find(|preset| preset.board == board)
+                    .map(|preset| preset.name.clone())
+            })
+    }
+}
+
+impl std::fmt::Display for BoardPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
     }
 }
 
@@ -0,0 +1,167 @@
+use crate::{base::board, save};
+use jiff::civil::Date;
+use log::{debug, error, info};
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+/// The daily board is an expert board.
+const WIDTH: usize = 30;
+const HEIGHT: usize = 16;
+const MINES: usize = 99;
+/// Everyone starts from the middle of the board, which is an opening.
+const FIRST_CLICK: (usize, usize) = (WIDTH / 2, HEIGHT / 2);
+
+/// The local date, which picks the daily board.
+pub fn today() -> Date {
+    jiff::Zoned::now().date()
+}
+
+/// The board of `date`, the same on every machine.
+///
+/// The seed only depends on the date, mixed with SplitMix64 so that consecutive days get
+/// unrelated boards.
+pub fn board_seed(date: Date) -> board::BoardSeed {
+    let days = date.year() as i64 * 10000 + date.month() as i64 * 100 + date.day() as i64;
+    let mut seed = (days as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    seed ^= seed >> 31;
+    board::BoardSeed {
+        width: WIDTH,
+        height: HEIGHT,
+        mines: MINES,
+        seed,
+        first_click: Some(FIRST_CLICK),
+        policy: board::FirstClickPolicy::Opening,
+    }
+}
+
+/// The finished game to record in [`DailyHistory::record`].
+#[derive(Debug, Clone, Copy)]
+pub struct Attempt {
+    pub won: bool,
+    /// Undo was used, so a win does not count as a best.
+    pub assisted: bool,
+    pub time: Duration,
+    pub bbbv: usize,
+    pub clicks: usize,
+}
+
+/// The best win on a daily board.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct Best {
+    pub time: Duration,
+    pub bbbv: usize,
+    pub clicks: usize,
+}
+
+impl Best {
+    pub fn bbbv_per_second(&self) -> f64 {
+        self.bbbv as f64 / self.time.as_secs_f64()
+    }
+
+    pub fn ioe(&self) -> f64 {
+        self.bbbv as f64 / self.clicks as f64
+    }
+}
+
+/// How a daily board went.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DayResult {
+    pub attempts: u32,
+    pub wins: u32,
+    pub best: Option<Best>,
+}
+
+/// Results of the daily boards played on this machine, by date.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DailyHistory {
+    #[serde(default)]
+    days: BTreeMap<String, DayResult>,
+}
+
+fn history_path() -> PathBuf {
+    save::save_dir().join("daily.toml")
+}
+
+impl DailyHistory {
+    /// The history on disk, empty if there is none or it cannot be read.
+    pub fn load() -> Self {
+        let path = history_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        debug!("Loading daily history from {:?}", path);
+        std::fs::read_to_string(&path)
+            .inspect_err(|e| error!("Failed to read daily history: {e}"))
+            .ok()
+            .and_then(|data| {
+                toml::from_str(&data)
+                    .inspect_err(|e| error!("Failed to deserialize daily history: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> crate::error::Result<()> {
+        let path = history_path();
+        debug!("Saving daily history to {:?}", path);
+        std::fs::create_dir_all(path.parent().unwrap())
+            .inspect_err(|e| error!("Failed to create save directory: {e}"))?;
+        let data = toml::to_string(self).inspect_err(|e| error!("Failed to serialize daily history: {e}"))?;
+        std::fs::write(&path, data).inspect_err(|e| error!("Failed to write daily history: {e}"))?;
+        Ok(())
+    }
+
+    pub fn result(&self, date: Date) -> Option<&DayResult> {
+        self.days.get(&date.to_string())
+    }
+
+    /// Add `attempt` to the results of `date` and write the history.
+    pub fn record(&mut self, date: Date, attempt: Attempt) -> crate::error::Result<()> {
+        let result = self.days.entry(date.to_string()).or_default();
+        result.attempts += 1;
+        if attempt.won {
+            result.wins += 1;
+            if !attempt.assisted && result.best.is_none_or(|best| attempt.time < best.time) {
+                info!("New best on the daily board of {}: {:?}", date, attempt.time);
+                result.best = Some(Best {
+                    time: attempt.time,
+                    bbbv: attempt.bbbv,
+                    clicks: attempt.clicks,
+                });
+            }
+        }
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::encode_decode::ascii;
+
+    /// Changing this board breaks every shared daily result, so it must stay the same.
+    #[test]
+    fn board_of_a_known_date() {
+        let board_seed = board_seed(jiff::civil::date(2025, 1, 1));
+        assert_eq!(board_seed.seed, 0xac32_ff5a_c20f_b509);
+        let expected = "\
+X........X...X.X......X.......
+...X......X.X.XX..X.X.........
+....XX..........X....X..XXX...
+X..X..X.......................
+X.........X........X......X.X.
+..X....X..XX.....X.X....X..XX.
+X.....XXX.X..X..X.......X..X..
+.XX.X..X.X...X............X...
+X.XX....X....X................
+XX..X..X..X............XX.....
+.X..X..X.X.XXX.X...X..X...X..X
+.....X.XX.......X....X...X....
+....X......X.X......X.X.X.....
+.X..XXX..........X.X.......X..
+.............X........X.......
+..X..........X................";
+        assert_eq!(ascii::encode(&board_seed.layout(), None).trim_end(), expected);
+    }
+}
@@ -1 +1,2 @@
+pub mod generator;
 pub mod solver;
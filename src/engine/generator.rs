@@ -0,0 +1,142 @@
+use crate::{
+    base::{
+        Vec2D,
+        board::{self, Board},
+    },
+    engine::solver::{BoardSafety, CellSafety, Solver, SolverExt, probability, trivial},
+};
+use log::{debug, info, trace};
+use rand::{Rng, rng};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Maximum number of layouts tried before giving up on a no-guessing board.
+const MAX_ATTEMPTS: usize = 10000;
+/// Longest time spent looking for a no-guessing board, as each attempt plays the whole board and
+/// large boards would otherwise keep going for minutes.
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Generate a board that can be solved from its start position without guessing.
+///
+/// Each attempt places mines randomly, keeping the 3x3 neighbourhood of a random start cell clear
+/// when the density allows it, then plays the board with `TrivialSolver` and
+/// `ProbabilityCalculator`, opening every cell proven safe and flagging every cell proven to be a
+/// mine. The first layout that is won this way is returned with its start position.
+///
+/// Returns `None` if there are no mines, if no such layout is found within `MAX_ATTEMPTS` attempts
+/// or `TIME_LIMIT`, or once `cancelled` is set.
+pub fn no_guess(width: usize, height: usize, mines: usize, cancelled: &AtomicBool) -> Option<board::ImportPack> {
+    let width = width.max(1);
+    let height = height.max(1);
+    if mines == 0 || mines >= width * height {
+        debug!(
+            "No-guess generation impossible: {} mines on {}x{}",
+            mines, width, height
+        );
+        return None;
+    }
+
+    let solver = trivial::TrivialSolver::new(false).then(probability::ProbabilityCalculator::new(false));
+    let mut rng = rng();
+    let deadline = Instant::now() + TIME_LIMIT;
+    for attempt in 1..=MAX_ATTEMPTS {
+        if cancelled.load(Ordering::Relaxed) {
+            debug!("No-guess generation cancelled after {} attempt(s)", attempt - 1);
+            return None;
+        }
+        if Instant::now() >= deadline {
+            info!(
+                "Failed to generate no-guess board {}x{} with {} mines in {:?}",
+                width, height, mines, TIME_LIMIT
+            );
+            return None;
+        }
+        let start_position = (rng.random_range(0..width), rng.random_range(0..height));
+        let mut cell_contents = Vec2D::new(width, height);
+        board::place_mines(
+            &mut cell_contents,
+            mines,
+            Some(start_position),
+            board::FirstClickPolicy::Opening,
+            &mut rng,
+        );
+        if solvable(&cell_contents, mines, start_position, &solver) {
+            info!(
+                "Generated no-guess board {}x{} with {} mines after {} attempt(s)",
+                width, height, mines, attempt
+            );
+            return Some(board::ImportPack {
+                cell_contents,
+                mines,
+                start_position: Some(start_position),
+                cell_states: None,
+                seed: None,
+            });
+        }
+        trace!("No-guess attempt {} failed", attempt);
+    }
+
+    info!(
+        "Failed to generate no-guess board {}x{} with {} mines in {} attempts",
+        width, height, mines, MAX_ATTEMPTS
+    );
+    None
+}
+
+/// Play the layout from `start_position` using only deductions made by `solver`.
+fn solvable(
+    cell_contents: &Vec2D<board::CellContent>,
+    mines: usize,
+    (sx, sy): (usize, usize),
+    solver: &impl Solver,
+) -> bool {
+    let pack = board::ImportPack {
+        cell_contents: cell_contents.clone(),
+        mines,
+        start_position: Some((sx, sy)),
+        cell_states: None,
+        seed: None,
+    };
+    let Some(mut board) = board::StandardBoard::import(pack, board::ChordMode::Standard) else {
+        return false;
+    };
+    board.left_click(sx, sy);
+
+    loop {
+        match board.state() {
+            board::BoardState::Won => return true,
+            board::BoardState::Lost { .. } => return false,
+            _ => {},
+        }
+
+        let safety = BoardSafety::new(board.cell_states(), mines, true);
+        let Ok(safety) = solver.calculate(safety) else {
+            return false;
+        };
+
+        let mut progressed = false;
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if board.cell_state(x, y) != Some(board::CellState::Closed) {
+                    continue;
+                }
+                match safety[(x, y)] {
+                    CellSafety::Safe => {
+                        board.left_click(x, y);
+                        progressed = true;
+                    },
+                    CellSafety::Mine => {
+                        board.right_click(x, y);
+                        progressed = true;
+                    },
+                    _ => {},
+                }
+            }
+        }
+        if !progressed {
+            return false;
+        }
+    }
+}
@@ -10,9 +10,13 @@ pub mod half_chance;
 pub mod probability;
 pub mod trivial;
 
-pub fn default_engine() -> impl Solver {
+/// The engine used by the solver overlays.
+///
+/// The lookahead metrics take an extra calculation per outcome of each candidate cell, so they are
+/// only computed with `lookahead`, see [`probability::ProbabilityCalculator::with_lookahead`].
+pub fn default_engine(lookahead: bool) -> impl Solver {
     trivial::TrivialSolver::new(false)
-        .then(probability::ProbabilityCalculator::new(false))
+        .then(probability::ProbabilityCalculator::new(false).with_lookahead(lookahead))
         .or(half_chance::HalfChanceCheck)
         .or(select(
             |board| board.conditions_more_than(1000.0),
@@ -21,12 +25,27 @@ pub fn default_engine() -> impl Solver {
         ))
 }
 
+/// Natural logarithm of the binomial coefficient C(n, k), or negative infinity if `k > n`.
+fn ln_binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k).map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln()).sum()
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CellProbability {
     pub frontier: bool,
+    /// The cell is part of a forced 50/50 that no further information can resolve.
+    pub half_chance: bool,
     pub mine_probability: f32,
+    /// Chance that opening the cell is safe and the best move after the revealed number is safe too.
     pub second_safety: f32,
+    /// Chance that opening the cell is safe and the revealed number proves other cells safe.
     pub progress_rate: f32,
+    /// Chance that opening the cell is safe and the revealed number determines all of its closed
+    /// neighbours.
     pub solve_rate: f32,
 }
 
@@ -34,6 +53,7 @@ impl Default for CellProbability {
     fn default() -> Self {
         CellProbability {
             frontier: false,
+            half_chance: false,
             mine_probability: f32::NAN,
             second_safety: f32::NAN,
             progress_rate: f32::NAN,
@@ -62,6 +82,16 @@ pub enum CellSafety {
     Probability(CellProbability),
 }
 
+impl CellSafety {
+    /// Whether the cell is closed and has not been determined to be safe or a mine.
+    pub fn is_unknown(&self) -> bool {
+        matches!(
+            self,
+            CellSafety::Wilderness | CellSafety::Frontier | CellSafety::Probability(..)
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BoardSafety {
     cells: Vec2D<CellSafety>,
@@ -124,6 +154,14 @@ impl BoardSafety {
         self.suggestion = Some((x, y));
     }
 
+    /// Iterate over the positions of the (up to 8) cells adjacent to `(x, y)`.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let (width, height) = (self.width(), self.height());
+        (x.saturating_sub(1)..=(x + 1).min(width - 1))
+            .flat_map(move |nx| (y.saturating_sub(1)..=(y + 1).min(height - 1)).map(move |ny| (nx, ny)))
+            .filter(move |&(nx, ny)| nx != x || ny != y)
+    }
+
     pub fn conditions_more_than(&self, count: f64) -> bool {
         let mut unconfirmed: usize = 0;
         let mut remaining_mines = self.mines;
@@ -140,8 +178,8 @@ impl BoardSafety {
         let n = remaining_mines.min(remaining_safe);
         let mut conditions = 1.0f64;
         for i in 0..n {
-            conditions /= i as f64;
-            conditions *= (unconfirmed - n + i) as f64;
+            conditions /= (i + 1) as f64;
+            conditions *= (unconfirmed - n + i + 1) as f64;
             if conditions > count {
                 return true;
             }
@@ -250,3 +288,45 @@ where
 }
 
 impl<T: Solver> SolverExt for T {}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::base::board::CellState;
+
+    /// Build a position from rows of `.` (closed), `F` (flagged) and digits (opened).
+    pub(super) fn position(rows: &[&str], mines: usize) -> BoardSafety {
+        let mut cell_states = Vec2D::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                cell_states[(x, y)] = match c {
+                    '.' => CellState::Closed,
+                    'F' => CellState::Flagged,
+                    _ => CellState::Opening(c.to_digit(10).unwrap() as u8),
+                };
+            }
+        }
+        BoardSafety::new(&cell_states, mines, true)
+    }
+
+    #[test]
+    fn lookahead_survives_brute_force() {
+        let board = position(&[".....", ".....", "1...."], 3);
+        assert!(!board.conditions_more_than(1000.0));
+        let board = default_engine(true).calculate(board).unwrap();
+        let metrics = board
+            .iter()
+            .filter_map(|cell| match cell {
+                CellSafety::Probability(probability) => Some(probability),
+                _ => None,
+            })
+            .filter(|probability| !probability.second_safety.is_nan())
+            .collect::<Vec<_>>();
+        assert!(!metrics.is_empty());
+        assert!(
+            metrics
+                .iter()
+                .all(|p| !p.progress_rate.is_nan() && !p.solve_rate.is_nan())
+        );
+    }
+}
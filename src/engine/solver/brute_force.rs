@@ -1,10 +1,333 @@
-use super::{BoardSafety, Solver};
+use super::{BoardSafety, CellProbability, CellSafety, Solver};
+use log::trace;
 
+/// An exact solver that enumerates every mine configuration consistent with the revealed numbers.
+///
+/// Closed cells adjacent to an unsolved number are enumerated one by one with backtracking, while
+/// the remaining (wilderness) cells are counted combinatorially: a frontier configuration with `m`
+/// mines stands for `C(wilderness, remaining_mines - m)` full configurations. The resulting
+/// probabilities are exact, which makes this solver suitable as a reference for small positions,
+/// but its running time grows exponentially with the frontier size.
 #[derive(Debug, Clone, Default)]
 pub struct BruteForceSolver;
 
+/// State of the backtracking enumeration over the frontier cells.
+struct Enumeration {
+    /// For each frontier cell, the indices of the witnesses around it.
+    cell_witnesses: Vec<Vec<usize>>,
+    /// For each witness, the number of mines still to be placed around it.
+    needed: Vec<usize>,
+    /// For each witness, the number of frontier cells around it not assigned yet.
+    unassigned: Vec<usize>,
+    /// Whether each frontier cell is a mine in the current configuration.
+    assignment: Vec<bool>,
+    /// Number of configurations, indexed by frontier mine count.
+    solutions: Vec<f64>,
+    /// Number of configurations where each frontier cell is a mine, indexed by frontier mine count.
+    cell_mines: Vec<Vec<f64>>,
+}
+
+impl Enumeration {
+    fn run(&mut self, index: usize, mines: usize) {
+        if index == self.assignment.len() {
+            self.solutions[mines] += 1.0;
+            for (i, &is_mine) in self.assignment.iter().enumerate() {
+                if is_mine {
+                    self.cell_mines[mines][i] += 1.0;
+                }
+            }
+            return;
+        }
+
+        for is_mine in [false, true] {
+            if is_mine && mines + 1 >= self.solutions.len() {
+                continue;
+            }
+            let consistent = self.cell_witnesses[index].iter().all(|&w| {
+                let needed = self.needed[w];
+                let unassigned = self.unassigned[w] - 1;
+                if is_mine {
+                    needed >= 1 && needed - 1 <= unassigned
+                } else {
+                    needed <= unassigned
+                }
+            });
+            if !consistent {
+                continue;
+            }
+            for &w in &self.cell_witnesses[index] {
+                self.unassigned[w] -= 1;
+                if is_mine {
+                    self.needed[w] -= 1;
+                }
+            }
+            self.assignment[index] = is_mine;
+            self.run(index + 1, mines + is_mine as usize);
+            self.assignment[index] = false;
+            for &w in &self.cell_witnesses[index] {
+                self.unassigned[w] += 1;
+                if is_mine {
+                    self.needed[w] += 1;
+                }
+            }
+        }
+    }
+}
+
 impl Solver for BruteForceSolver {
-    fn calculate(&self, board: BoardSafety) -> super::error::Result<BoardSafety> {
+    fn calculate(&self, mut board: BoardSafety) -> super::error::Result<BoardSafety> {
+        let mut known_mines = 0;
+        let mut frontier = Vec::new();
+        let mut wilderness = Vec::new();
+        let mut witnesses = Vec::new();
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                match board[(x, y)] {
+                    CellSafety::Mine => known_mines += 1,
+                    CellSafety::Unsolved(n) => witnesses.push((x, y, n)),
+                    ref cell if cell.is_unknown() => {
+                        if board
+                            .neighbors(x, y)
+                            .any(|(nx, ny)| matches!(board[(nx, ny)], CellSafety::Unsolved(_)))
+                        {
+                            frontier.push((x, y));
+                        } else {
+                            wilderness.push((x, y));
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        if frontier.is_empty() && wilderness.is_empty() {
+            return Ok(board);
+        }
+
+        let Some(remaining_mines) = board.mines().checked_sub(known_mines) else {
+            return Err(super::error::Error::TooFewMines(board.mines()));
+        };
+
+        let mut needed = Vec::with_capacity(witnesses.len());
+        let mut unassigned = Vec::with_capacity(witnesses.len());
+        for &(x, y, n) in &witnesses {
+            let mut flagged = 0u8;
+            let mut unknown = 0u8;
+            for (nx, ny) in board.neighbors(x, y) {
+                match board[(nx, ny)] {
+                    CellSafety::Mine => flagged += 1,
+                    ref cell if cell.is_unknown() => unknown += 1,
+                    _ => {},
+                }
+            }
+            if flagged > n || flagged + unknown < n {
+                return Err(super::error::Error::MinesNotSatisfied {
+                    x,
+                    y,
+                    expected: n,
+                    actual: if flagged > n { flagged } else { flagged + unknown },
+                });
+            }
+            needed.push((n - flagged) as usize);
+            unassigned.push(unknown as usize);
+        }
+
+        let cell_witnesses = frontier
+            .iter()
+            .map(|&(x, y)| {
+                witnesses
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (wx, wy, _))| wx.abs_diff(x) <= 1 && wy.abs_diff(y) <= 1)
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+
+        let max_frontier_mines = remaining_mines.min(frontier.len());
+        let mut enumeration = Enumeration {
+            cell_witnesses,
+            needed,
+            unassigned,
+            assignment: vec![false; frontier.len()],
+            solutions: vec![0.0; max_frontier_mines + 1],
+            cell_mines: vec![vec![0.0; frontier.len()]; max_frontier_mines + 1],
+        };
+        enumeration.run(0, 0);
+        trace!(
+            "BruteForceSolver: {} frontier cells, {} wilderness cells, {} frontier configurations",
+            frontier.len(),
+            wilderness.len(),
+            enumeration.solutions.iter().sum::<f64>()
+        );
+
+        // Each frontier configuration with `m` mines is weighted by the number of ways to place the
+        // remaining mines in the wilderness. Weights are computed in log space relative to the
+        // largest one to avoid overflow.
+        let ln_weights: Vec<Option<f64>> = enumeration
+            .solutions
+            .iter()
+            .enumerate()
+            .map(|(m, &count)| {
+                (count > 0.0 && remaining_mines - m <= wilderness.len())
+                    .then(|| count.ln() + super::ln_binomial(wilderness.len(), remaining_mines - m))
+            })
+            .collect();
+        let Some(max_ln_weight) = ln_weights.iter().flatten().copied().reduce(f64::max) else {
+            if enumeration.solutions.iter().all(|&count| count == 0.0) {
+                return Err(super::error::Error::NoSolution);
+            } else if remaining_mines > frontier.len() + wilderness.len() {
+                return Err(super::error::Error::TooManyMines(board.mines()));
+            } else {
+                return Err(super::error::Error::TooFewMines(board.mines()));
+            }
+        };
+
+        let mut total = 0.0f64;
+        let mut frontier_tallies = vec![0.0f64; frontier.len()];
+        let mut wilderness_tally = 0.0f64;
+        for (m, ln_weight) in ln_weights.iter().enumerate() {
+            let Some(ln_weight) = ln_weight else {
+                continue;
+            };
+            let weight = (ln_weight - max_ln_weight).exp();
+            let per_configuration = weight / enumeration.solutions[m];
+            total += weight;
+            for (tally, &count) in frontier_tallies.iter_mut().zip(&enumeration.cell_mines[m]) {
+                *tally += count * per_configuration;
+            }
+            if !wilderness.is_empty() {
+                wilderness_tally += weight * (remaining_mines - m) as f64 / wilderness.len() as f64;
+            }
+        }
+
+        let unknown_neighbors = |(x, y): (usize, usize)| {
+            board
+                .neighbors(x, y)
+                .filter(|&(nx, ny)| board[(nx, ny)].is_unknown())
+                .count()
+        };
+        let probabilities = frontier
+            .iter()
+            .copied()
+            .zip(frontier_tallies.iter().map(|tally| tally / total))
+            .map(|(pos, p)| (pos, p, true))
+            .chain(wilderness.iter().map(|&pos| (pos, wilderness_tally / total, false)))
+            .map(|(pos, p, frontier)| (pos, p, frontier, unknown_neighbors(pos)))
+            .collect::<Vec<_>>();
+
+        // The best guess is the safest cell; among equally safe cells, the one with the fewest
+        // unknown neighbors is preferred since its number is the most likely to resolve them.
+        let mut best: Option<((usize, usize), f64, usize)> = None;
+        for &((x, y), probability, frontier, unknown_neighbors) in &probabilities {
+            let probability = probability.clamp(0.0, 1.0);
+            if probability <= f64::EPSILON {
+                board[(x, y)] = CellSafety::Safe;
+                if board.suggestion().is_none() {
+                    board.suggest(x, y);
+                }
+                continue;
+            } else if probability >= 1.0 - f64::EPSILON {
+                board[(x, y)] = CellSafety::Mine;
+                continue;
+            }
+            // Keep the lookahead metrics of an earlier stage, only the probability is refined
+            match &mut board[(x, y)] {
+                CellSafety::Probability(cell) => {
+                    cell.frontier = frontier;
+                    cell.mine_probability = probability as f32;
+                },
+                cell => {
+                    *cell = CellSafety::Probability(CellProbability {
+                        frontier,
+                        mine_probability: probability as f32,
+                        ..Default::default()
+                    })
+                },
+            }
+
+            let better = match best {
+                None => true,
+                Some((_, best_probability, best_unknown)) => {
+                    probability < best_probability - f64::EPSILON
+                        || (probability <= best_probability + f64::EPSILON && unknown_neighbors < best_unknown)
+                },
+            };
+            if better {
+                best = Some(((x, y), probability, unknown_neighbors));
+            }
+        }
+
+        if board.suggestion().is_none()
+            && let Some(((x, y), probability, _)) = best
+        {
+            trace!(
+                "BruteForceSolver: suggesting ({}, {}) with mine probability {}",
+                x, y, probability
+            );
+            board.suggest(x, y);
+        }
+
         Ok(board)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::solver::{probability::ProbabilityCalculator, tests::position};
+
+    /// Check that both solvers agree on every closed cell of the position.
+    fn assert_agree(rows: &[&str], mines: usize) {
+        let board = position(rows, mines);
+        let exact = BruteForceSolver.calculate(board.clone()).unwrap();
+        let calculated = ProbabilityCalculator::new(false).calculate(board.clone()).unwrap();
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if !board[(x, y)].is_unknown() {
+                    continue;
+                }
+                match (exact[(x, y)], calculated[(x, y)]) {
+                    (CellSafety::Safe, CellSafety::Safe) | (CellSafety::Mine, CellSafety::Mine) => {},
+                    (CellSafety::Probability(a), CellSafety::Probability(b)) => {
+                        assert!(
+                            (a.mine_probability - b.mine_probability).abs() < 1e-5,
+                            "({x}, {y}): {} != {}",
+                            a.mine_probability,
+                            b.mine_probability
+                        );
+                        assert_eq!(a.frontier, b.frontier, "({x}, {y})");
+                    },
+                    (a, b) => panic!("({x}, {y}): {a:?} != {b:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wilderness_only() {
+        let rows = ["....", "....", "....", "...."];
+        assert!(!position(&rows, 3).conditions_more_than(1000.0));
+        assert_agree(&rows, 3);
+    }
+
+    #[test]
+    fn one_two_one() {
+        assert_agree(&["...", "121"], 2);
+    }
+
+    #[test]
+    fn frontier_and_wilderness() {
+        assert_agree(&[".....", ".....", "1...."], 4);
+    }
+
+    #[test]
+    fn flagged_and_safe_cells() {
+        assert_agree(&["....", "F2..", "11.."], 4);
+    }
+
+    #[test]
+    fn two_frontier_groups() {
+        assert_agree(&["......", "......", "1....1", "1....1"], 5);
+    }
+}
@@ -8,6 +8,7 @@ pub enum Error {
     },
     TooManyMines(usize),
     TooFewMines(usize),
+    NoSolution,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -28,6 +29,9 @@ impl std::fmt::Display for Error {
             Error::TooFewMines(actual) => {
                 write!(f, "{} mines is too few to complete the board", actual)
             },
+            Error::NoSolution => {
+                write!(f, "No mine configuration satisfies the revealed numbers")
+            },
         }
     }
 }
@@ -1,10 +1,143 @@
-use super::{BoardSafety, Solver};
+use super::{BoardSafety, CellSafety, Solver};
+use log::trace;
 
+/// Candidates must be at least this fraction as likely to be safe as the safest cell.
+const SAFETY_TOLERANCE: f64 = 0.95;
+/// Weight of the chance that a guess opens new safe cells.
+const PROGRESS_WEIGHT: f64 = 0.5;
+/// Weight of the (normalised) entropy of the number revealed by a guess.
+const INFORMATION_WEIGHT: f64 = 0.1;
+
+/// A heuristic guesser for positions where the probabilities are known but exact lookahead is too
+/// expensive.
+///
+/// Only cells nearly as safe as the safest one are considered, so that survival always comes first.
+/// Among them, each candidate is scored by its chance of being safe, weighted up by:
+///
+/// - progress: the chance that the revealed number is already satisfied by known mines, making all
+///   of its other closed neighbours safe;
+/// - information: the entropy of the revealed number, which measures how much it tells about the
+///   neighbourhood even when it does not resolve it.
+///
+/// Neighbours are treated as independent with their own mine probability, which is exact for
+/// wilderness cells and a reasonable approximation on the frontier. Cells without a computed
+/// probability use the average density of the remaining mines. The progress is taken from
+/// `CellProbability::progress_rate` instead when it has been computed.
 #[derive(Debug, Clone, Default)]
 pub struct GuessingLogic;
 
+struct Candidate {
+    position: (usize, usize),
+    safety: f64,
+    progress: f64,
+    information: f64,
+}
+
+impl Candidate {
+    fn score(&self) -> f64 {
+        self.safety * (1.0 + PROGRESS_WEIGHT * self.progress + INFORMATION_WEIGHT * self.information)
+    }
+}
+
+impl GuessingLogic {
+    fn mine_probability(cell: &CellSafety, density: f64) -> Option<f64> {
+        match cell {
+            CellSafety::Probability(probability) if !probability.mine_probability.is_nan() => {
+                Some(probability.mine_probability as f64)
+            },
+            CellSafety::Wilderness | CellSafety::Frontier | CellSafety::Probability(..) => Some(density),
+            _ => None,
+        }
+    }
+
+    /// Evaluate opening `(x, y)`, assuming it is safe.
+    fn evaluate(board: &BoardSafety, x: usize, y: usize, safety: f64, density: f64) -> Candidate {
+        // Distribution of the number of mines among the unknown neighbours.
+        let mut distribution = vec![1.0f64];
+        for (nx, ny) in board.neighbors(x, y) {
+            let Some(p) = Self::mine_probability(&board[(nx, ny)], density) else {
+                continue;
+            };
+            let mut next = vec![0.0; distribution.len() + 1];
+            for (mines, &chance) in distribution.iter().enumerate() {
+                next[mines] += chance * (1.0 - p);
+                next[mines + 1] += chance * p;
+            }
+            distribution = next;
+        }
+
+        // Prefer the exact rate from the probability lookahead when it is available
+        let progress = match board[(x, y)] {
+            CellSafety::Probability(probability) if !probability.progress_rate.is_nan() && safety > 0.0 => {
+                probability.progress_rate as f64 / safety
+            },
+            _ if distribution.len() > 1 => distribution[0],
+            _ => 0.0,
+        };
+        let entropy: f64 = distribution
+            .iter()
+            .filter(|&&chance| chance > 0.0)
+            .map(|&chance| -chance * chance.log2())
+            .sum();
+
+        Candidate {
+            position: (x, y),
+            safety,
+            progress,
+            // A number cell has at most 9 possible values
+            information: entropy / 9f64.log2(),
+        }
+    }
+}
+
 impl Solver for GuessingLogic {
-    fn calculate(&self, board: BoardSafety) -> super::error::Result<BoardSafety> {
+    fn calculate(&self, mut board: BoardSafety) -> super::error::Result<BoardSafety> {
+        if board.suggestion().is_some() {
+            return Ok(board);
+        }
+
+        let mut unknown = 0;
+        let mut known_mines = 0;
+        for cell in board.iter() {
+            match cell {
+                CellSafety::Mine => known_mines += 1,
+                cell if cell.is_unknown() => unknown += 1,
+                _ => {},
+            }
+        }
+        if unknown == 0 {
+            return Ok(board);
+        }
+        let Some(remaining_mines) = board.mines().checked_sub(known_mines) else {
+            return Err(super::error::Error::TooFewMines(board.mines()));
+        };
+        let density = (remaining_mines as f64 / unknown as f64).min(1.0);
+
+        let mut safeties = Vec::with_capacity(unknown);
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if let Some(p) = Self::mine_probability(&board[(x, y)], density) {
+                    safeties.push(((x, y), 1.0 - p));
+                }
+            }
+        }
+        let best_safety = safeties.iter().map(|&(_, safety)| safety).fold(0.0, f64::max);
+
+        let best = safeties
+            .into_iter()
+            .filter(|&(_, safety)| safety >= best_safety * SAFETY_TOLERANCE)
+            .map(|((x, y), safety)| Self::evaluate(&board, x, y, safety, density))
+            .max_by(|a, b| a.score().total_cmp(&b.score()));
+
+        if let Some(best) = best {
+            trace!(
+                "GuessingLogic: suggesting {:?} with safety {}, progress {}, information {}",
+                best.position, best.safety, best.progress, best.information
+            );
+            let (x, y) = best.position;
+            board.suggest(x, y);
+        }
+
         Ok(board)
     }
 }
@@ -1,10 +1,143 @@
-use super::{BoardSafety, Solver};
+use super::{BoardSafety, CellProbability, CellSafety, Solver};
+use log::trace;
 
+/// Detects forced 50/50 guesses, i.e. groups of closed cells that hold exactly one of two mine
+/// layouts which no further information can ever tell apart.
+///
+/// Two-cell groups (including the classic edge pattern) and 2x2 blocks with diagonal mines are
+/// checked. A group is a forced guess when:
+///
+/// - the numbers whose unknown neighbours all lie inside the group leave exactly two layouts, with
+///   the same number of mines;
+/// - every other cell touching the group that is not a known mine, whether a revealed number or a
+///   closed cell that may be revealed later, sees the same number of mines under both layouts.
+///
+/// Since the guess has to be taken sooner or later, it is suggested right away so that no effort is
+/// wasted on the rest of the board before it. All cells of every forced guess found are marked with
+/// `CellProbability::half_chance`.
 #[derive(Debug, Clone, Default)]
 pub struct HalfChanceCheck;
 
+impl HalfChanceCheck {
+    /// Offsets of the second cell of a two-cell group relative to the first one, each unordered
+    /// pair being visited once.
+    const PAIR_OFFSETS: [(isize, isize); 4] = [(1, 0), (-1, 1), (0, 1), (1, 1)];
+
+    /// Returns the two indistinguishable layouts of `group` as bit masks over its cells, or `None`
+    /// if `group` is not a forced guess.
+    fn forced_layouts(board: &BoardSafety, group: &[(usize, usize)]) -> Option<(u32, u32)> {
+        let mask_of = |x: usize, y: usize| {
+            group
+                .iter()
+                .enumerate()
+                .filter(|(_, (gx, gy))| gx.abs_diff(x) <= 1 && gy.abs_diff(y) <= 1 && (*gx, *gy) != (x, y))
+                .fold(0u32, |mask, (i, _)| mask | (1 << i))
+        };
+
+        let mut touching = group
+            .iter()
+            .flat_map(|&(x, y)| board.neighbors(x, y))
+            .filter(|pos| !group.contains(pos))
+            .collect::<Vec<_>>();
+        touching.sort_unstable();
+        touching.dedup();
+
+        // Constraints of the numbers whose unknown neighbours all lie inside the group.
+        let mut constraints = Vec::new();
+        for &(x, y) in &touching {
+            let CellSafety::Unsolved(n) = board[(x, y)] else {
+                continue;
+            };
+            let mut flagged = 0u8;
+            let mut outside = false;
+            for (nx, ny) in board.neighbors(x, y) {
+                match board[(nx, ny)] {
+                    CellSafety::Mine => flagged += 1,
+                    ref cell if cell.is_unknown() => outside |= !group.contains(&(nx, ny)),
+                    _ => {},
+                }
+            }
+            if !outside {
+                constraints.push((mask_of(x, y), n.checked_sub(flagged)? as u32));
+            }
+        }
+        if constraints.is_empty() {
+            return None;
+        }
+
+        let mut layouts = (0..1u32 << group.len()).filter(|layout| {
+            constraints
+                .iter()
+                .all(|&(mask, needed)| (layout & mask).count_ones() == needed)
+        });
+        let (Some(first), Some(second), None) = (layouts.next(), layouts.next(), layouts.next()) else {
+            return None;
+        };
+        if first.count_ones() != second.count_ones() {
+            return None;
+        }
+
+        touching
+            .iter()
+            .filter(|&&(x, y)| !matches!(board[(x, y)], CellSafety::Mine))
+            .all(|&(x, y)| {
+                let mask = mask_of(x, y);
+                (first & mask).count_ones() == (second & mask).count_ones()
+            })
+            .then_some((first, second))
+    }
+}
+
 impl Solver for HalfChanceCheck {
-    fn calculate(&self, board: BoardSafety) -> super::error::Result<BoardSafety> {
+    fn calculate(&self, mut board: BoardSafety) -> super::error::Result<BoardSafety> {
+        let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if !board[(x, y)].is_unknown() {
+                    continue;
+                }
+                for (dx, dy) in Self::PAIR_OFFSETS {
+                    let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                        continue;
+                    };
+                    if board.get(nx, ny).is_some_and(|cell| cell.is_unknown()) {
+                        groups.push(vec![(x, y), (nx, ny)]);
+                    }
+                }
+                let block = [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)];
+                if block
+                    .iter()
+                    .all(|&(bx, by)| board.get(bx, by).is_some_and(|cell| cell.is_unknown()))
+                {
+                    groups.push(block.to_vec());
+                }
+            }
+        }
+
+        for group in groups {
+            let Some((first, second)) = Self::forced_layouts(&board, &group) else {
+                continue;
+            };
+            trace!("HalfChanceCheck: forced guess found at {:?}", group);
+            for (i, &(x, y)) in group.iter().enumerate() {
+                let mut probability = match board[(x, y)] {
+                    CellSafety::Probability(probability) => probability,
+                    CellSafety::Frontier => CellProbability {
+                        frontier: true,
+                        ..Default::default()
+                    },
+                    _ => CellProbability::default(),
+                };
+                probability.half_chance = true;
+                probability.mine_probability = ((first >> i & 1) + (second >> i & 1)) as f32 / 2.0;
+                board[(x, y)] = CellSafety::Probability(probability);
+            }
+            if board.suggestion().is_none() {
+                let (x, y) = group[0];
+                board.suggest(x, y);
+            }
+        }
+
         Ok(board)
     }
 }
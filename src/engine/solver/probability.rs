@@ -12,9 +12,16 @@ type SmallVec<T> = smallvec::SmallVec<[T; 8]>;
 const MAX_BINOMIAL_N: usize = 170;
 const MAX_BINOMIAL_K: usize = 85;
 
+/// Maximum number of cells evaluated by the lookahead.
+const MAX_LOOKAHEAD_CELLS: usize = 32;
+/// Cells at least this fraction as likely to be safe as the safest cell are evaluated by the
+/// lookahead.
+const LOOKAHEAD_TOLERANCE: f64 = 0.9;
+
 #[derive(Debug, Clone, Default)]
 pub struct ProbabilityCalculator {
     stop_on_first_safe: bool,
+    lookahead: bool,
 }
 
 /// Represents a numbered cell (witness) that constrains adjacent frontier cells
@@ -59,7 +66,23 @@ impl ProbabilityLine {
 
 impl ProbabilityCalculator {
     pub fn new(stop_on_first_safe: bool) -> Self {
-        Self { stop_on_first_safe }
+        Self {
+            stop_on_first_safe,
+            lookahead: false,
+        }
+    }
+
+    /// Also compute `second_safety`, `progress_rate` and `solve_rate` for the safest cells when
+    /// no safe cell is found.
+    ///
+    /// Each candidate is revealed with every number it could show, and the position that follows is
+    /// calculated again. The number of configurations of each outcome relative to the current
+    /// position gives its exact chance, so the cost is one extra calculation per outcome and
+    /// candidate. Only the `MAX_LOOKAHEAD_CELLS` safest cells within `LOOKAHEAD_TOLERANCE` of the
+    /// safest one are evaluated.
+    pub fn with_lookahead(mut self, lookahead: bool) -> Self {
+        self.lookahead = lookahead;
+        self
     }
 
     /// Calculate binomial coefficient C(n, k) = n! / (k! * (n-k)!)
@@ -89,20 +112,24 @@ impl ProbabilityCalculator {
         for y in 0..board.height() {
             for x in 0..board.width() {
                 if let CellSafety::Unsolved(n) = board[(x, y)] {
-                    // Count already flagged mines
+                    // Count already flagged mines and frontier cells
                     let mut flagged = 0;
+                    let mut frontier = 0;
                     for nx in x.saturating_sub(1)..=(x + 1).min(board.width() - 1) {
                         for ny in y.saturating_sub(1)..=(y + 1).min(board.height() - 1) {
                             if nx == x && ny == y {
                                 continue;
                             }
-                            if matches!(board[(nx, ny)], CellSafety::Mine) {
-                                flagged += 1;
+                            match board[(nx, ny)] {
+                                CellSafety::Mine => flagged += 1,
+                                CellSafety::Frontier => frontier += 1,
+                                _ => {},
                             }
                         }
                     }
 
-                    if n > flagged {
+                    // A satisfied number still witnesses that its frontier cells hold no mines
+                    if n > flagged || (n == flagged && frontier > 0) {
                         let uid = witnesses.len();
                         witnesses.push(Witness {
                             uid,
@@ -413,11 +440,15 @@ impl ProbabilityCalculator {
     }
 }
 
-impl Solver for ProbabilityCalculator {
-    fn calculate(&self, mut board: BoardSafety) -> super::error::Result<BoardSafety> {
+impl ProbabilityCalculator {
+    /// Compute the probabilities of every closed cell.
+    ///
+    /// Also returns the natural logarithm of the number of mine configurations consistent with the
+    /// board (negative infinity if there is none), or NaN if the calculation stopped early.
+    fn solve(&self, mut board: BoardSafety) -> (BoardSafety, f64) {
         if self.stop_on_first_safe && board.suggestion().is_some() {
             trace!("ProbabilityCalculator: Stopping early due to existing suggestion");
-            return Ok(board);
+            return (board, f64::NAN);
         }
 
         let (mut witnesses, mut boxes) = self.build_witnesses_and_boxes(&board);
@@ -446,30 +477,25 @@ impl Solver for ProbabilityCalculator {
 
                 for y in 0..board.height() {
                     for x in 0..board.width() {
-                        match board[(x, y)] {
-                            CellSafety::Frontier => {
-                                if self.set_probability(&mut board, x, y, uniform_probability, true) {
-                                    return Ok(board);
-                                }
-                            },
-                            CellSafety::Wilderness => {
-                                if self.set_probability(&mut board, x, y, uniform_probability, false) {
-                                    return Ok(board);
-                                }
-                            },
-                            _ => {},
+                        let frontier = match board[(x, y)] {
+                            CellSafety::Frontier => true,
+                            CellSafety::Wilderness => false,
+                            _ => continue,
+                        };
+                        if self.set_probability(&mut board, x, y, uniform_probability, frontier) {
+                            return (board, f64::NAN);
                         }
                     }
                 }
             }
 
-            return Ok(board);
+            return (board, super::ln_binomial(total_cells, mines_left));
         }
 
         // If no boxes but there are witnesses, something is wrong
         if boxes.is_empty() {
             trace!("ProbabilityCalculator: Witnesses found but no boxes - this shouldn't happen");
-            return Ok(board);
+            return (board, f64::NAN);
         }
 
         trace!(
@@ -525,7 +551,7 @@ impl Solver for ProbabilityCalculator {
                     held_probs =
                         self.store_probabilities(held_probs, working_probs, max_total_mines, &boxes, &mut board);
                     if self.stop_on_first_safe && board.suggestion().is_some() {
-                        return Ok(board);
+                        return (board, f64::NAN);
                     }
                     working_probs = vec![ProbabilityLine::new(box_count)];
                     current_witness = Some(next_wit);
@@ -536,7 +562,11 @@ impl Solver for ProbabilityCalculator {
         // Store final working probabilities
         held_probs = self.store_probabilities(held_probs, working_probs, max_total_mines, &boxes, &mut board);
         if self.stop_on_first_safe && board.suggestion().is_some() {
-            return Ok(board);
+            return (board, f64::NAN);
+        }
+        if held_probs.is_empty() {
+            trace!("ProbabilityCalculator: No mine configuration satisfies the witnesses");
+            return (board, f64::NEG_INFINITY);
         }
 
         // Calculate final probabilities for each box
@@ -582,6 +612,8 @@ impl Solver for ProbabilityCalculator {
         }
 
         trace!("ProbabilityCalculator: Total tally = {}", total_tally);
+        // Weights are relative to `e * C(tiles_off_edge, min_mine)`, see `ln_weight` above
+        let ln_solutions = total_tally.ln() - 1.0 + super::ln_binomial(tiles_off_edge, min_mine);
 
         // Update board with calculated probabilities
         if total_tally > 0.0 {
@@ -591,7 +623,7 @@ impl Solver for ProbabilityCalculator {
 
                 for &(x, y) in &box_data.cells {
                     if self.set_probability(&mut board, x, y, probability, true) {
-                        return Ok(board);
+                        return (board, f64::NAN);
                     }
                 }
             }
@@ -607,7 +639,7 @@ impl Solver for ProbabilityCalculator {
                     if matches!(board[(x, y)], CellSafety::Wilderness)
                         && self.set_probability(&mut board, x, y, off_edge_prob, false)
                     {
-                        return Ok(board);
+                        return (board, f64::NAN);
                     }
                 }
             }
@@ -633,6 +665,168 @@ impl Solver for ProbabilityCalculator {
             }
         }
 
+        (board, ln_solutions)
+    }
+}
+
+impl ProbabilityCalculator {
+    fn lookahead(&self, board: &mut BoardSafety, ln_solutions: f64) {
+        // The position to reveal candidates on, with the probabilities cleared
+        let mut template = board.clone();
+        for cell in template.iter_mut() {
+            if let CellSafety::Probability(probability) = *cell {
+                *cell = if probability.frontier {
+                    CellSafety::Frontier
+                } else {
+                    CellSafety::Wilderness
+                };
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                if let CellSafety::Probability(probability) = board[(x, y)] {
+                    candidates.push(((x, y), 1.0 - probability.mine_probability as f64));
+                }
+            }
+        }
+        let best_safety = candidates.iter().map(|&(_, safety)| safety).fold(0.0, f64::max);
+        candidates.retain(|&(_, safety)| safety >= best_safety * LOOKAHEAD_TOLERANCE);
+        candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        candidates.truncate(MAX_LOOKAHEAD_CELLS);
+        trace!("ProbabilityCalculator: Looking ahead on {} cells", candidates.len());
+
+        let known_mines = template.iter().filter(|cell| matches!(cell, CellSafety::Mine)).count();
+        let remaining_mines = template.mines().saturating_sub(known_mines);
+        let calculator = ProbabilityCalculator::new(false);
+        for ((x, y), _) in candidates {
+            let mut flagged = 0;
+            let mut unknown: SmallVec<(usize, usize)> = SmallVec::new();
+            for (nx, ny) in template.neighbors(x, y) {
+                match template[(nx, ny)] {
+                    CellSafety::Mine => flagged += 1,
+                    CellSafety::Frontier | CellSafety::Wilderness => unknown.push((nx, ny)),
+                    _ => {},
+                }
+            }
+
+            let (mut second_safety, mut progress_rate, mut solve_rate) = (0.0f64, 0.0f64, 0.0f64);
+            for mines in 0..=unknown.len().min(remaining_mines) {
+                let mut revealed = template.clone();
+                let number = (flagged + mines) as u8;
+                if mines == 0 {
+                    revealed[(x, y)] = CellSafety::Solved(number);
+                    for &pos in &unknown {
+                        revealed[pos] = CellSafety::Safe;
+                    }
+                } else if mines == unknown.len() {
+                    revealed[(x, y)] = CellSafety::Solved(number);
+                    for &pos in &unknown {
+                        revealed[pos] = CellSafety::Mine;
+                    }
+                    // Flagging every neighbour may exceed the numbers around them, or satisfy them so
+                    // that their other closed neighbours are safe
+                    let mut witnesses: SmallVec<(usize, usize)> = SmallVec::new();
+                    for &(ux, uy) in &unknown {
+                        for (wx, wy) in revealed.neighbors(ux, uy) {
+                            if matches!(revealed[(wx, wy)], CellSafety::Unsolved(_)) && !witnesses.contains(&(wx, wy)) {
+                                witnesses.push((wx, wy));
+                            }
+                        }
+                    }
+                    let mut overflagged = false;
+                    for (wx, wy) in witnesses {
+                        let CellSafety::Unsolved(n) = revealed[(wx, wy)] else {
+                            continue;
+                        };
+                        let flagged = revealed
+                            .neighbors(wx, wy)
+                            .filter(|&pos| matches!(revealed[pos], CellSafety::Mine))
+                            .count();
+                        if flagged > n as usize {
+                            overflagged = true;
+                            break;
+                        } else if flagged == n as usize {
+                            revealed[(wx, wy)] = CellSafety::Solved(n);
+                            for pos in revealed.neighbors(wx, wy) {
+                                if matches!(revealed[pos], CellSafety::Frontier) {
+                                    revealed[pos] = CellSafety::Safe;
+                                }
+                            }
+                        }
+                    }
+                    if overflagged {
+                        continue;
+                    }
+                } else {
+                    revealed[(x, y)] = CellSafety::Unsolved(number);
+                    for &pos in &unknown {
+                        revealed[pos] = CellSafety::Frontier;
+                    }
+                }
+
+                let (result, ln_outcome) = calculator.solve(revealed);
+                if !ln_outcome.is_finite() {
+                    continue;
+                }
+                let chance = (ln_outcome - ln_solutions).exp();
+
+                let progress = result
+                    .iter()
+                    .zip(template.iter())
+                    .any(|(after, before)| matches!(after, CellSafety::Safe) && !matches!(before, CellSafety::Safe));
+                let solved = unknown
+                    .iter()
+                    .all(|&pos| matches!(result[pos], CellSafety::Safe | CellSafety::Mine));
+                let next_safety = if progress {
+                    1.0
+                } else {
+                    result
+                        .iter()
+                        .filter_map(|cell| match cell {
+                            CellSafety::Probability(probability) => Some(1.0 - probability.mine_probability as f64),
+                            _ => None,
+                        })
+                        .reduce(f64::max)
+                        .unwrap_or(1.0)
+                };
+
+                second_safety += chance * next_safety;
+                progress_rate += chance * progress as u8 as f64;
+                solve_rate += chance * solved as u8 as f64;
+            }
+
+            if let CellSafety::Probability(probability) = &mut board[(x, y)] {
+                probability.second_safety = second_safety.clamp(0.0, 1.0) as f32;
+                probability.progress_rate = progress_rate.clamp(0.0, 1.0) as f32;
+                probability.solve_rate = solve_rate.clamp(0.0, 1.0) as f32;
+            }
+        }
+    }
+}
+
+impl Solver for ProbabilityCalculator {
+    fn calculate(&self, board: BoardSafety) -> super::error::Result<BoardSafety> {
+        let (mut board, ln_solutions) = self.solve(board);
+        if self.lookahead && board.suggestion().is_none() && ln_solutions.is_finite() {
+            self.lookahead(&mut board, ln_solutions);
+        }
         Ok(board)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::solver::tests::position;
+
+    #[test]
+    fn number_satisfied_by_flags() {
+        // The 1 at the bottom is satisfied by the flag, so the two cells right of it are safe
+        let board = position(&["....", "F2..", "11.."], 4);
+        let board = ProbabilityCalculator::new(false).calculate(board).unwrap();
+        assert!(matches!(board[(2, 1)], CellSafety::Safe));
+        assert!(matches!(board[(2, 2)], CellSafety::Safe));
+    }
+}
@@ -3,6 +3,7 @@ pub enum Error {
     IO(std::io::Error),
     MissingResource(String),
     SkinNotFound(String),
+    InvalidSkin(String),
     FileNotFound(String),
     TomlSerialize(toml::ser::Error),
     TomlDeserialize(toml::de::Error),
@@ -11,6 +12,7 @@ pub enum Error {
     PixmapCreationFailed,
     Iced(iced::Error),
     Solver(crate::engine::solver::error::Error),
+    InvalidSave(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -63,6 +65,7 @@ impl std::fmt::Display for Error {
             Self::IO(e) => write!(f, "IO error: {e}"),
             Self::MissingResource(resource) => write!(f, "missing resource: {resource}"),
             Self::SkinNotFound(skin) => write!(f, "invalid skin: {skin}"),
+            Self::InvalidSkin(reason) => write!(f, "invalid skin config: {reason}"),
             Self::FileNotFound(file) => write!(f, "file not found: {file}"),
             Self::TomlSerialize(e) => write!(f, "TOML serialization error: {e}"),
             Self::TomlDeserialize(e) => write!(f, "TOML deserialization error: {e}"),
@@ -71,6 +74,7 @@ impl std::fmt::Display for Error {
             Self::PixmapCreationFailed => write!(f, "failed to create pixmap"),
             Self::Iced(e) => write!(f, "iced error: {e}"),
             Self::Solver(e) => write!(f, "solver error: {e}"),
+            Self::InvalidSave(reason) => write!(f, "invalid save: {reason}"),
         }
     }
 }
@@ -81,6 +85,7 @@ impl std::error::Error for Error {
             Self::IO(e) => e.source(),
             Self::MissingResource(_) => None,
             Self::SkinNotFound(_) => None,
+            Self::InvalidSkin(_) => None,
             Self::FileNotFound(_) => None,
             Self::TomlSerialize(e) => e.source(),
             Self::TomlDeserialize(e) => e.source(),
@@ -89,6 +94,7 @@ impl std::error::Error for Error {
             Self::PixmapCreationFailed => None,
             Self::Iced(e) => e.source(),
             Self::Solver(e) => e.source(),
+            Self::InvalidSave(_) => None,
         }
     }
 }
@@ -0,0 +1,100 @@
+use crate::{base::board, config, save};
+use log::{debug, error, info};
+use std::{io::Write, path::PathBuf, time::Duration};
+
+/// A finished game.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameRecord {
+    /// When the game ended, in RFC 3339.
+    pub finished_at: String,
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub time: Duration,
+    pub won: bool,
+    pub left_clicks: usize,
+    pub right_clicks: usize,
+    pub chord_clicks: usize,
+    pub bbbv: usize,
+    pub solved_bbbv: usize,
+    pub chord_mode: board::ChordMode,
+    /// The solver overlay was shown at some point of the game.
+    pub solver: bool,
+    /// Undo was used.
+    pub assisted: bool,
+    /// The preset the board belonged to when the game was played.
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+impl GameRecord {
+    pub fn clicks(&self) -> usize {
+        self.left_clicks + self.right_clicks + self.chord_clicks
+    }
+
+    pub fn bbbv_per_second(&self) -> f64 {
+        self.solved_bbbv as f64 / self.time.as_secs_f64()
+    }
+
+    /// Whether the game may set a personal best: won without undo or the solver.
+    pub fn is_clean_win(&self) -> bool {
+        self.won && !self.assisted && !self.solver
+    }
+
+    /// The name of the board the game was played on, which groups the records: its preset if
+    /// any, otherwise its size.
+    pub fn board_name(&self) -> String {
+        let board = [self.width, self.height, self.mines];
+        self.preset
+            .clone()
+            .or_else(|| config::BoardPreset::name_of(board, &[]))
+            .unwrap_or_else(|| format!("{}x{} ({})", self.width, self.height, self.mines))
+    }
+}
+
+/// Records are appended as `[[games]]` tables, so the file stays valid TOML without rewriting it.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct History {
+    #[serde(default)]
+    games: Vec<GameRecord>,
+}
+
+fn history_path() -> PathBuf {
+    save::save_dir().join("history.toml")
+}
+
+/// Add `record` to the end of the history file.
+pub fn append(record: GameRecord) -> crate::error::Result<()> {
+    let path = history_path();
+    debug!("Appending game record to {:?}", path);
+    std::fs::create_dir_all(path.parent().unwrap()).inspect_err(|e| error!("Failed to create save directory: {e}"))?;
+    let data = toml::to_string(&History { games: vec![record] })
+        .inspect_err(|e| error!("Failed to serialize game record: {e}"))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .inspect_err(|e| error!("Failed to open history file: {e}"))?;
+    writeln!(file, "\n{}", data.trim_end()).inspect_err(|e| error!("Failed to write history file: {e}"))?;
+    Ok(())
+}
+
+/// Every recorded game, oldest first. Empty if there is no history or it cannot be read.
+pub fn load() -> Vec<GameRecord> {
+    let path = history_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+    debug!("Loading game history from {:?}", path);
+    let history: History = std::fs::read_to_string(&path)
+        .inspect_err(|e| error!("Failed to read history file: {e}"))
+        .ok()
+        .and_then(|data| {
+            toml::from_str(&data)
+                .inspect_err(|e| error!("Failed to deserialize history file: {e}"))
+                .ok()
+        })
+        .unwrap_or_default();
+    info!("Loaded {} game record(s)", history.games.len());
+    history.games
+}
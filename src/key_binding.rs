@@ -0,0 +1,336 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+/// What a key binding does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    NewGame,
+    Replay,
+    Continue,
+    ToggleProbability,
+    ToggleAdmitFlags,
+    Import,
+    Export,
+    Undo,
+    Redo,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 11] = [
+        KeyAction::NewGame,
+        KeyAction::Replay,
+        KeyAction::Continue,
+        KeyAction::ToggleProbability,
+        KeyAction::ToggleAdmitFlags,
+        KeyAction::Import,
+        KeyAction::Export,
+        KeyAction::Undo,
+        KeyAction::Redo,
+        KeyAction::ZoomIn,
+        KeyAction::ZoomOut,
+    ];
+}
+
+impl std::fmt::Display for KeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyAction::NewGame => write!(f, "New Game"),
+            KeyAction::Replay => write!(f, "Replay"),
+            KeyAction::Continue => write!(f, "Continue"),
+            KeyAction::ToggleProbability => write!(f, "Show Probability"),
+            KeyAction::ToggleAdmitFlags => write!(f, "Admits Flags"),
+            KeyAction::Import => write!(f, "Import"),
+            KeyAction::Export => write!(f, "Export"),
+            KeyAction::Undo => write!(f, "Undo"),
+            KeyAction::Redo => write!(f, "Redo"),
+            KeyAction::ZoomIn => write!(f, "Zoom In"),
+            KeyAction::ZoomOut => write!(f, "Zoom Out"),
+        }
+    }
+}
+
+/// A key with its modifiers, written like `Ctrl+Shift+Z` in the config.
+///
+/// The default binding is unbound and written as an empty string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    /// `Z` for a character key, the name of the key otherwise, e.g. `Space` or `F2`.
+    key: String,
+    /// Ctrl, or Cmd on macOS.
+    command: bool,
+    shift: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    const COMMAND: &str = "Ctrl+";
+    const SHIFT: &str = "Shift+";
+    const ALT: &str = "Alt+";
+
+    /// The named keys that can be bound.
+    const NAMED_KEYS: [Named; 27] = [
+        Named::Space,
+        Named::Enter,
+        Named::Tab,
+        Named::Backspace,
+        Named::Delete,
+        Named::Insert,
+        Named::Home,
+        Named::End,
+        Named::PageUp,
+        Named::PageDown,
+        Named::ArrowUp,
+        Named::ArrowDown,
+        Named::ArrowLeft,
+        Named::ArrowRight,
+        Named::Escape,
+        Named::F1,
+        Named::F2,
+        Named::F3,
+        Named::F4,
+        Named::F5,
+        Named::F6,
+        Named::F7,
+        Named::F8,
+        Named::F9,
+        Named::F10,
+        Named::F11,
+        Named::F12,
+    ];
+
+    /// The keys of the keyboard cursor, see `CursorAction::from_key`, which only move it without
+    /// Ctrl or Alt.
+    const CURSOR_KEYS: [&str; 13] = [
+        "ArrowUp",
+        "ArrowDown",
+        "ArrowLeft",
+        "ArrowRight",
+        "Enter",
+        "Escape",
+        "H",
+        "J",
+        "K",
+        "L",
+        "D",
+        "F",
+        "S",
+    ];
+
+    fn new(key: &str, command: bool, shift: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            command,
+            shift,
+            alt: false,
+        }
+    }
+
+    /// The binding of a key press, `None` for keys that cannot be bound, e.g. a modifier alone.
+    pub fn from_event(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        let key = match key.as_ref() {
+            Key::Named(named) if Self::NAMED_KEYS.contains(&named) => format!("{:?}", named),
+            Key::Named(_) => return None,
+            Key::Character(character) => character.to_uppercase(),
+            Key::Unidentified => return None,
+        };
+        Some(Self {
+            key,
+            command: modifiers.command(),
+            shift: modifiers.shift(),
+            alt: modifiers.alt(),
+        })
+    }
+
+    pub fn is_unbound(&self) -> bool {
+        self.key.is_empty()
+    }
+
+    /// The key moves the keyboard cursor, so it would never reach the cursor if bound.
+    pub fn is_cursor_key(&self) -> bool {
+        !self.command && !self.alt && Self::CURSOR_KEYS.contains(&self.key.as_str())
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.command {
+            write!(f, "{}", Self::COMMAND)?;
+        }
+        if self.shift {
+            write!(f, "{}", Self::SHIFT)?;
+        }
+        if self.alt {
+            write!(f, "{}", Self::ALT)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut binding = KeyBinding::default();
+        let mut rest = value.trim();
+        // The key itself may be `+`, so only prefixes followed by more text are modifiers
+        loop {
+            if let Some(stripped) = rest.strip_prefix(Self::COMMAND).filter(|s| !s.is_empty()) {
+                binding.command = true;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix(Self::SHIFT).filter(|s| !s.is_empty()) {
+                binding.shift = true;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix(Self::ALT).filter(|s| !s.is_empty()) {
+                binding.alt = true;
+                rest = stripped;
+            } else {
+                break;
+            }
+        }
+        // Named keys are written like `ArrowUp`
+        if rest.chars().count() > 1 && !Self::NAMED_KEYS.iter().any(|named| format!("{:?}", named) == rest) {
+            return Err(format!("Invalid key binding '{}'", value));
+        }
+        binding.key = if rest.chars().count() == 1 {
+            rest.to_uppercase()
+        } else {
+            rest.to_string()
+        };
+        Ok(binding)
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(value: KeyBinding) -> Self {
+        value.to_string()
+    }
+}
+
+/// The key of each [`KeyAction`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub new_game: KeyBinding,
+    pub replay: KeyBinding,
+    #[serde(rename = "continue")]
+    pub continue_: KeyBinding,
+    pub toggle_probability: KeyBinding,
+    pub toggle_admit_flags: KeyBinding,
+    pub import: KeyBinding,
+    pub export: KeyBinding,
+    pub undo: KeyBinding,
+    pub redo: KeyBinding,
+    pub zoom_in: KeyBinding,
+    pub zoom_out: KeyBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            new_game: KeyBinding::new("Space", false, false),
+            replay: KeyBinding::new("R", false, false),
+            continue_: KeyBinding::new("C", false, false),
+            toggle_probability: KeyBinding::new("P", false, false),
+            toggle_admit_flags: KeyBinding::new("A", false, false),
+            import: KeyBinding::new("I", true, false),
+            export: KeyBinding::new("E", true, false),
+            undo: KeyBinding::new("Z", true, false),
+            redo: KeyBinding::new("Z", true, true),
+            zoom_in: KeyBinding::new("=", true, false),
+            zoom_out: KeyBinding::new("-", true, false),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: KeyAction) -> &KeyBinding {
+        match action {
+            KeyAction::NewGame => &self.new_game,
+            KeyAction::Replay => &self.replay,
+            KeyAction::Continue => &self.continue_,
+            KeyAction::ToggleProbability => &self.toggle_probability,
+            KeyAction::ToggleAdmitFlags => &self.toggle_admit_flags,
+            KeyAction::Import => &self.import,
+            KeyAction::Export => &self.export,
+            KeyAction::Undo => &self.undo,
+            KeyAction::Redo => &self.redo,
+            KeyAction::ZoomIn => &self.zoom_in,
+            KeyAction::ZoomOut => &self.zoom_out,
+        }
+    }
+
+    fn get_mut(&mut self, action: KeyAction) -> &mut KeyBinding {
+        match action {
+            KeyAction::NewGame => &mut self.new_game,
+            KeyAction::Replay => &mut self.replay,
+            KeyAction::Continue => &mut self.continue_,
+            KeyAction::ToggleProbability => &mut self.toggle_probability,
+            KeyAction::ToggleAdmitFlags => &mut self.toggle_admit_flags,
+            KeyAction::Import => &mut self.import,
+            KeyAction::Export => &mut self.export,
+            KeyAction::Undo => &mut self.undo,
+            KeyAction::Redo => &mut self.redo,
+            KeyAction::ZoomIn => &mut self.zoom_in,
+            KeyAction::ZoomOut => &mut self.zoom_out,
+        }
+    }
+
+    /// Bind `binding` to `action`, taking it from the action it was bound to if any.
+    ///
+    /// Fails if `binding` is a key of the keyboard cursor.
+    pub fn set(&mut self, action: KeyAction, binding: KeyBinding) -> Result<(), String> {
+        if binding.is_cursor_key() {
+            return Err(format!(
+                "{} moves the keyboard cursor, add Ctrl or Alt to bind it",
+                binding
+            ));
+        }
+        if !binding.is_unbound()
+            && let Some(previous) = self.action_of(&binding)
+        {
+            *self.get_mut(previous) = KeyBinding::default();
+        }
+        *self.get_mut(action) = binding;
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, action: KeyAction) {
+        *self.get_mut(action) = KeyBinding::default();
+    }
+
+    /// The action bound to `binding`, if any.
+    pub fn action_of(&self, binding: &KeyBinding) -> Option<KeyAction> {
+        if binding.is_unbound() {
+            return None;
+        }
+        KeyAction::ALL.into_iter().find(|&action| self.get(action) == binding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bindings() {
+        for text in ["Ctrl+Shift+Z", "F2", "Space", "Alt++", "ArrowUp"] {
+            let binding = KeyBinding::try_from(text.to_string()).unwrap();
+            assert_eq!(binding.to_string(), text);
+        }
+        assert!(KeyBinding::try_from("Foo".to_string()).is_err());
+        assert!(KeyBinding::try_from("Ctrl+Shift".to_string()).is_err());
+    }
+
+    #[test]
+    fn cursor_keys_cannot_be_bound() {
+        let mut bindings = KeyBindings::default();
+        let d = KeyBinding::try_from("D".to_string()).unwrap();
+        assert!(bindings.set(KeyAction::NewGame, d).is_err());
+        assert_eq!(bindings.new_game, KeyBindings::default().new_game);
+        let ctrl_d = KeyBinding::try_from("Ctrl+D".to_string()).unwrap();
+        assert!(bindings.set(KeyAction::NewGame, ctrl_d.clone()).is_ok());
+        assert_eq!(bindings.action_of(&ctrl_d), Some(KeyAction::NewGame));
+    }
+}
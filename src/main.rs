@@ -5,8 +5,12 @@
 
 mod base;
 mod config;
+mod daily;
 mod engine;
 mod error;
+mod history;
+mod key_binding;
+mod save;
 mod single_instance;
 mod ui;
 mod utils;
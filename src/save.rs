@@ -0,0 +1,164 @@
+use crate::base::{board, encode_decode};
+use log::{debug, error, info};
+use std::{path::PathBuf, time::Duration};
+
+/// A started game, with everything needed to continue playing it.
+///
+/// The position is kept as ASCII with the cell states, see [`encode_decode::ascii::decode_with_states`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedGame {
+    position: String,
+    start_position: Option<(usize, usize)>,
+    state: board::BoardState,
+    chord_mode: board::ChordMode,
+    elapsed: Duration,
+    assisted: bool,
+    /// See [`encode_decode::seed`], saves made before boards had seeds have none.
+    #[serde(default)]
+    seed: Option<String>,
+}
+
+/// Where the app keeps its data, next to the config.
+pub fn save_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or(".".into()).join(env!("CARGO_CRATE_NAME"))
+}
+
+fn autosave_path() -> PathBuf {
+    save_dir().join("autosave.toml")
+}
+
+fn saved_games_dir() -> PathBuf {
+    save_dir().join("saves")
+}
+
+/// The path of the saved game `name`, which must be a plain file name.
+fn saved_game_path(name: &str) -> crate::error::Result<PathBuf> {
+    let valid = !name.trim().is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    if !valid {
+        error!("Invalid saved game name: {:?}", name);
+        return Err(crate::error::Error::InvalidSave(format!(
+            "invalid name {name:?}, use letters, digits, spaces, '-' and '_'"
+        )));
+    }
+    Ok(saved_games_dir().join(format!("{}.toml", name.trim())))
+}
+
+/// Names of the saved games, sorted.
+pub fn saved_games() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(saved_games_dir()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "toml").then(|| path.file_stem()?.to_str().map(str::to_owned))?
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+impl SavedGame {
+    /// Snapshot `board`, `None` if the game has not started yet.
+    pub fn new(board: &dyn board::Board, elapsed: Duration, assisted: bool) -> Option<Self> {
+        if board.state() == board::BoardState::NotStarted {
+            return None;
+        }
+        Some(Self {
+            position: encode_decode::ascii::encode_with_states(
+                board.cell_contents(),
+                board.cell_states(),
+                board.start_position(),
+            ),
+            start_position: board.start_position(),
+            state: board.state(),
+            chord_mode: board.chord_mode(),
+            elapsed,
+            assisted,
+            seed: board.board_seed().as_ref().map(encode_decode::seed::encode),
+        })
+    }
+
+    /// Rebuild the board, `None` if the save is corrupted.
+    pub fn board(&self) -> Option<board::StandardBoard> {
+        let pack = board::ImportPack {
+            start_position: self.start_position,
+            seed: self.seed.as_deref().and_then(encode_decode::seed::parse),
+            ..encode_decode::ascii::decode_with_states(&self.position)?
+        };
+        board::StandardBoard::restore(pack, self.state, self.chord_mode)
+    }
+
+    pub fn chord_mode(&self) -> board::ChordMode {
+        self.chord_mode
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn is_assisted(&self) -> bool {
+        self.assisted
+    }
+
+    fn write(&self, path: PathBuf) -> crate::error::Result<()> {
+        debug!("Saving game to {:?}", path);
+        std::fs::create_dir_all(path.parent().unwrap())
+            .inspect_err(|e| error!("Failed to create save directory: {e}"))?;
+        let data = toml::to_string(self).inspect_err(|e| error!("Failed to serialize game: {e}"))?;
+        std::fs::write(&path, data).inspect_err(|e| error!("Failed to write save file: {e}"))?;
+        Ok(())
+    }
+
+    fn read(path: PathBuf) -> crate::error::Result<Self> {
+        debug!("Loading game from {:?}", path);
+        let data = std::fs::read_to_string(path).inspect_err(|e| error!("Failed to read save file: {e}"))?;
+        let saved = toml::from_str(&data).inspect_err(|e| error!("Failed to deserialize game: {e}"))?;
+        Ok(saved)
+    }
+
+    /// Keep the game to be restored on the next launch.
+    pub fn autosave(&self) -> crate::error::Result<()> {
+        self.write(autosave_path())?;
+        info!("Game saved for the next launch");
+        Ok(())
+    }
+
+    /// The game kept by [`SavedGame::autosave`], `None` if there is none.
+    pub fn load_autosave() -> crate::error::Result<Option<Self>> {
+        let path = autosave_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::read(path).map(Some)
+    }
+
+    /// Forget the game kept by [`SavedGame::autosave`], if any.
+    pub fn remove_autosave() {
+        let path = autosave_path();
+        if path.exists() {
+            debug!("Removing autosave {:?}", path);
+            _ = std::fs::remove_file(path).inspect_err(|e| error!("Failed to remove autosave: {e}"));
+        }
+    }
+
+    pub fn save_as(&self, name: &str) -> crate::error::Result<()> {
+        self.write(saved_game_path(name)?)?;
+        info!("Game saved as {:?}", name);
+        Ok(())
+    }
+
+    pub fn load(name: &str) -> crate::error::Result<Self> {
+        Self::read(saved_game_path(name)?)
+    }
+
+    pub fn delete(name: &str) -> crate::error::Result<()> {
+        std::fs::remove_file(saved_game_path(name)?)
+            .inspect_err(|e| error!("Failed to delete saved game {:?}: {e}", name))?;
+        info!("Saved game {:?} deleted", name);
+        Ok(())
+    }
+}
@@ -7,5 +7,6 @@ mod modal;
 mod overlay;
 mod player;
 pub mod skin;
+mod statistics;
 
 pub use app::{App, AppMessage};
@@ -1,17 +1,385 @@
+use crate::{
+    base::{board, encode_decode},
+    engine::solver,
+    ui::{board_area::BoardArea, board_frame::BoardFrame, *},
+};
+use iced::{Function, Task};
+use log::{debug, info, trace};
+use std::sync::Arc;
+
 mod editor;
+pub use editor::{EditorMessage, EditorTool};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeInputType {
+    Width = 0,
+    Height = 1,
+}
 
-pub enum AnalyzerMessage {}
+#[derive(Debug, Clone)]
+pub enum AnalyzerMessage {
+    Editor(EditorMessage),
+    Solver(overlay::SolverOverlayMessage),
+    UpdateSkin {
+        skin: Arc<skin::Skin>,
+        cell_size: u32,
+    },
+    Scrolled(iced::widget::scrollable::Viewport),
+    SizeInputChanged(SizeInputType, String),
+    /// Start over with an empty board of the size in the inputs.
+    NewBoard,
+    /// Edit the position of the game being played, handled by the main window.
+    LoadGame,
+    ExportTypeSelected(encode_decode::EncodeType),
+    Export,
+    ExportCompleted(String),
+}
 
-pub struct Analyzer {}
+/// Paints positions by hand and shows what the solver makes of them.
+pub struct Analyzer {
+    skin: Arc<skin::Skin>,
+    cell_size: u32,
+    board_frame: BoardFrame,
+    editor: editor::Editor,
+    solver_overlay: overlay::SolverOverlay,
+    solver_admit_flags: bool,
+    solver_display: overlay::ProbabilityDisplay,
+    update_solver_in_progress: bool,
+    update_solver_scheduled: bool,
+    size_input_states: [String; 2],
+    export_type: encode_decode::EncodeType,
+    viewport: iced::Rectangle,
+}
 
 impl Analyzer {
-    pub fn new() -> Self {
-        Self {}
+    const SIZE_INPUT_UPPER: usize = 1000;
+    const SIZE_INPUT_LOWER: usize = 1;
+
+    pub fn new(width: usize, height: usize, cell_size: u32, skin: Arc<skin::Skin>) -> Self {
+        let board_area = BoardArea::calculate(&skin, cell_size, width, height);
+        let mut solver_overlay = overlay::SolverOverlay::new(
+            solver::default_engine(false),
+            solver::default_engine(true),
+            board_area,
+            cell_size,
+        );
+        solver_overlay.update(overlay::SolverOverlayMessage::SetLightSkin(skin.light));
+        solver_overlay.update(overlay::SolverOverlayMessage::SetEnabled(true));
+        Self {
+            board_frame: BoardFrame::new(board_area, Arc::clone(&skin)),
+            editor: editor::Editor::new(width, height, board_area, cell_size, Arc::clone(&skin)),
+            skin,
+            cell_size,
+            solver_overlay,
+            solver_admit_flags: false,
+            solver_display: overlay::ProbabilityDisplay::default(),
+            update_solver_in_progress: false,
+            update_solver_scheduled: false,
+            size_input_states: [width.to_string(), height.to_string()],
+            export_type: encode_decode::EncodeType::AsciiWithStates,
+            viewport: Default::default(),
+        }
     }
 
-    pub fn update(&mut self) {}
+    /// Lay out the board again after its size, the skin or the cell size changed.
+    fn relayout(&mut self) {
+        let board_area = BoardArea::calculate(&self.skin, self.cell_size, self.editor.width(), self.editor.height());
+        self.board_frame = BoardFrame::new(board_area, Arc::clone(&self.skin));
+        self.editor.resize(self.cell_size, board_area, Arc::clone(&self.skin));
+        self.solver_overlay.update(overlay::SolverOverlayMessage::Resize {
+            cell_size: self.cell_size,
+            board_area,
+        });
+        self.solver_overlay
+            .update(overlay::SolverOverlayMessage::SetLightSkin(self.skin.light));
+        self.size_input_states = [self.editor.width().to_string(), self.editor.height().to_string()];
+    }
+
+    /// Edit the position of `board`.
+    pub fn load(&mut self, board: &dyn board::Board) -> Task<AnalyzerMessage> {
+        self.editor.load(board);
+        self.relayout();
+        self.solver_overlay.clear_solver();
+        self.update_solver().unwrap_or_else(Task::none)
+    }
+
+    /// Set the size of the visible part of the board, e.g. when switching to the analyzer.
+    pub fn set_viewport(&mut self, viewport: iced::Rectangle) {
+        self.viewport = viewport;
+        self.editor.update(EditorMessage::ViewportChanged(viewport));
+        self.solver_overlay.set_viewport(viewport);
+    }
+
+    fn update_solver(&mut self) -> Option<Task<AnalyzerMessage>> {
+        if self.update_solver_in_progress {
+            debug!("Solver update already in progress, scheduling another update");
+            self.update_solver_scheduled = true;
+            return None;
+        }
+        // Positions the board rejects, like a flagged cell next to a wrong number, cannot be solved
+        let Some(board) = self.editor.board() else {
+            debug!("Edited position is not a valid board, skipping solver update");
+            self.solver_overlay.clear_solver();
+            return None;
+        };
+        debug!("Starting solver update");
+        self.update_solver_in_progress = true;
+        Some(self.solver_overlay.update_solver(&board).map(AnalyzerMessage::Solver))
+    }
+
+    pub fn update(&mut self, message: AnalyzerMessage) -> Task<AnalyzerMessage> {
+        trace!("AnalyzerMessage received: {:?}", message);
+        let mut tasks = vec![];
+        match message {
+            AnalyzerMessage::Editor(msg) => {
+                if let EditorMessage::ViewportChanged(viewport) = msg {
+                    self.set_viewport(viewport);
+                } else if self.editor.update(msg)
+                    && let Some(task) = self.update_solver()
+                {
+                    tasks.push(task);
+                }
+            },
+            AnalyzerMessage::Solver(msg) => match &msg {
+                overlay::SolverOverlayMessage::SetAdmitFlags(admit_flags) => {
+                    debug!("Analyzer solver admit flags changed: {}", admit_flags);
+                    self.solver_admit_flags = *admit_flags;
+                    self.solver_overlay.update(msg);
+                    if let Some(task) = self.update_solver() {
+                        tasks.push(task);
+                    }
+                },
+                overlay::SolverOverlayMessage::SetDisplay(display) => {
+                    debug!("Analyzer solver display changed: {}", display);
+                    let recalculate = display.needs_lookahead() && !self.solver_display.needs_lookahead();
+                    self.solver_display = *display;
+                    self.solver_overlay.update(msg);
+                    if recalculate && let Some(task) = self.update_solver() {
+                        tasks.push(task);
+                    }
+                },
+                overlay::SolverOverlayMessage::SolverCompleted(..) => {
+                    self.solver_overlay.update(msg);
+                    self.update_solver_in_progress = false;
+                    if self.update_solver_scheduled {
+                        debug!("Running scheduled solver update");
+                        self.update_solver_scheduled = false;
+                        if let Some(task) = self.update_solver() {
+                            tasks.push(task);
+                        }
+                    }
+                },
+                _ => self.solver_overlay.update(msg),
+            },
+            AnalyzerMessage::UpdateSkin { skin, cell_size } => {
+                trace!("Updating skin in Analyzer");
+                self.skin = skin;
+                self.cell_size = cell_size;
+                self.relayout();
+            },
+            AnalyzerMessage::Scrolled(viewport) => {
+                let absolute_offset = viewport.absolute_offset();
+                let bounds = viewport.bounds();
+                self.set_viewport(iced::Rectangle {
+                    x: absolute_offset.x,
+                    y: absolute_offset.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                });
+            },
+            AnalyzerMessage::SizeInputChanged(input_type, value) => {
+                self.size_input_states[input_type as usize] = value;
+            },
+            AnalyzerMessage::NewBoard => {
+                let [width, height] = self.size_input_states.each_ref().map(|value| {
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .unwrap_or(Self::SIZE_INPUT_LOWER)
+                        .clamp(Self::SIZE_INPUT_LOWER, Self::SIZE_INPUT_UPPER)
+                });
+                info!("Creating empty {}x{} board in the analyzer", width, height);
+                self.editor = editor::Editor::new(
+                    width,
+                    height,
+                    BoardArea::default(),
+                    self.cell_size,
+                    Arc::clone(&self.skin),
+                );
+                self.editor.update(EditorMessage::ViewportChanged(self.viewport));
+                self.relayout();
+                self.solver_overlay.clear_solver();
+                if let Some(task) = self.update_solver() {
+                    tasks.push(task);
+                }
+            },
+            AnalyzerMessage::LoadGame => {
+                // Handled by the main window, which has the game
+            },
+            AnalyzerMessage::ExportTypeSelected(export_type) => {
+                debug!("Analyzer export type: {}", export_type);
+                self.export_type = export_type;
+            },
+            AnalyzerMessage::Export => {
+                debug!("Start export edited position with type {}", self.export_type);
+                let encode_type = self.export_type;
+                let pack = board::ImportPack {
+                    cell_contents: self.editor.cell_contents().clone(),
+                    mines: self.editor.mines(),
+                    start_position: None,
+                    cell_states: Some(self.editor.cell_states().clone()),
+                    seed: None,
+                };
+                tasks.push(Task::perform(
+                    // Painted positions have no seed, which is not offered here
+                    async move { encode_type.encode(&pack).unwrap_or_default() },
+                    AnalyzerMessage::ExportCompleted,
+                ));
+            },
+            AnalyzerMessage::ExportCompleted(data) => {
+                info!("Edited position exported successfully to clipboard");
+                trace!("Encoded data: {}", data);
+                tasks.push(iced::clipboard::write(data));
+            },
+        }
+        Task::batch(tasks)
+    }
+
+    pub fn view_sidebar(&self, width: f32) -> iced::Element<'_, AnalyzerMessage> {
+        let board_control = iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Board").size(18)),
+                iced::widget::row![
+                    iced::widget::text("Width:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::TextInput::new("width", &self.size_input_states[SizeInputType::Width as usize])
+                        .width(iced::FillPortion(2))
+                        .on_input(AnalyzerMessage::SizeInputChanged.with(SizeInputType::Width))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::row![
+                    iced::widget::text("Height:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::TextInput::new("height", &self.size_input_states[SizeInputType::Height as usize])
+                        .width(iced::FillPortion(2))
+                        .on_input(AnalyzerMessage::SizeInputChanged.with(SizeInputType::Height))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::center_x(
+                    iced::widget::button(iced::widget::text("Clear").align_x(iced::alignment::Horizontal::Center))
+                        .width(width * 0.6)
+                        .on_press(AnalyzerMessage::NewBoard)
+                ),
+                iced::widget::center_x(
+                    iced::widget::button(iced::widget::text("Load Game").align_x(iced::alignment::Horizontal::Center))
+                        .width(width * 0.6)
+                        .on_press(AnalyzerMessage::LoadGame)
+                ),
+                iced::widget::center_x(iced::widget::text(format!("Mines: {}", self.editor.mines())).size(16)),
+            ]
+            .spacing(4)
+            .padding(6)
+            .width(width * 0.9),
+        )
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        });
+
+        let export_control = iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Export").size(18)),
+                iced::widget::pick_list(
+                    encode_decode::EncodeType::ENCODE_TYPES
+                        .into_iter()
+                        .filter(|&encode_type| encode_type != encode_decode::EncodeType::Seed)
+                        .collect::<Vec<_>>(),
+                    Some(self.export_type),
+                    AnalyzerMessage::ExportTypeSelected
+                )
+                .width(iced::Fill),
+                iced::widget::center_x(
+                    iced::widget::button(iced::widget::text("Copy").align_x(iced::alignment::Horizontal::Center))
+                        .width(width * 0.6)
+                        .on_press(AnalyzerMessage::Export)
+                ),
+            ]
+            .spacing(4)
+            .padding(6)
+            .width(width * 0.9),
+        )
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        });
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(board_control),
+                iced::widget::row![
+                    iced::widget::text("Paint:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(&EditorTool::ALL[..], Some(self.editor.tool()), |tool| {
+                        AnalyzerMessage::Editor(EditorMessage::SetTool(tool))
+                    })
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::checkbox(self.editor.shows_mines())
+                    .label("Show Mines")
+                    .on_toggle(|show_mines| AnalyzerMessage::Editor(EditorMessage::SetShowMines(show_mines))),
+                iced::widget::checkbox(self.solver_admit_flags)
+                    .label("Admits Flags")
+                    .on_toggle(|admit_flags| {
+                        AnalyzerMessage::Solver(overlay::SolverOverlayMessage::SetAdmitFlags(admit_flags))
+                    }),
+                iced::widget::row![
+                    iced::widget::text("Display:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(
+                        &overlay::ProbabilityDisplay::ALL[..],
+                        Some(self.solver_display),
+                        |display| AnalyzerMessage::Solver(overlay::SolverOverlayMessage::SetDisplay(display))
+                    )
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::center_x(export_control),
+                iced::widget::text("Left click paints, right click erases. Drag to paint several cells.").size(14),
+            ]
+            .spacing(4)
+            .padding(4),
+        )
+        .width(iced::Length::Fixed(width))
+        .into()
+    }
+
+    pub fn view_board(&self) -> iced::Element<'_, AnalyzerMessage> {
+        iced::widget::Stack::with_capacity(3)
+            .push(self.board_frame.view())
+            .push(self.editor.view().map(AnalyzerMessage::Editor))
+            .push(self.solver_overlay.view().map(AnalyzerMessage::Solver))
+            .into()
+    }
 
-    pub fn view(&self) -> iced::Element<'_, AnalyzerMessage> {
-        iced::widget::scrollable("todo").into()
+    pub fn subscriptions(&self) -> iced::Subscription<AnalyzerMessage> {
+        iced::event::listen_with(|event, _, _| match event {
+            iced::Event::Window(iced::window::Event::Resized(size)) => {
+                trace!("Window resized to size: {:?}", size);
+                Some(AnalyzerMessage::Editor(EditorMessage::ViewportChanged(
+                    iced::Rectangle {
+                        x: 0.0,
+                        y: 0.0,
+                        width: size.width,
+                        height: size.height,
+                    },
+                )))
+            },
+            _ => None,
+        })
     }
 }
@@ -1,383 +1,414 @@
 use crate::{
-    base::{Vec2D, board},
-    config::GlobalConfig,
-    ui::skin,
+    base::{
+        Vec2D,
+        board::{self, CellContent, CellState},
+    },
+    ui::{board_area::BoardArea, skin},
 };
 use iced::widget::canvas;
 use log::{debug, trace};
+use std::sync::Arc;
+
+/// What a left click paints on a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditorTool {
+    /// Place a closed mine.
+    #[default]
+    Mine,
+    /// Open a safe cell, showing its number.
+    Reveal,
+    /// Flag a cell, mine or not.
+    Flag,
+    /// Close a cell, keeping its content.
+    Close,
+}
+
+impl EditorTool {
+    pub const ALL: [EditorTool; 4] = [
+        EditorTool::Mine,
+        EditorTool::Reveal,
+        EditorTool::Flag,
+        EditorTool::Close,
+    ];
+}
+
+impl std::fmt::Display for EditorTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditorTool::Mine => write!(f, "Mine"),
+            EditorTool::Reveal => write!(f, "Reveal"),
+            EditorTool::Flag => write!(f, "Flag"),
+            EditorTool::Close => write!(f, "Close"),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
-pub struct EditorMessage {}
+pub enum EditorMessage {
+    /// Apply the current tool to a cell.
+    Paint {
+        x: usize,
+        y: usize,
+    },
+    /// Make a cell a closed safe cell.
+    Erase {
+        x: usize,
+        y: usize,
+    },
+    SetTool(EditorTool),
+    SetShowMines(bool),
+    ViewportChanged(iced::Rectangle),
+}
+
+/// The mouse button held down over the editor and the last cell it painted.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PaintState {
+    #[default]
+    Idle,
+    Painting(Option<(usize, usize)>),
+    Erasing(Option<(usize, usize)>),
+}
 
+/// Paints a position cell by cell: the mine layout together with what has been opened and flagged.
+///
+/// Numbers always follow the mines around them, so the position can be played and analysed.
 pub struct Editor {
-    board: Vec2D<board::CellState>,
+    cell_contents: Vec2D<CellContent>,
+    cell_states: Vec2D<CellState>,
     mines: usize,
-    editor_area: iced::Rectangle,
-    top_area: iced::Rectangle,
-    board_area: iced::Rectangle,
-    counter_area: iced::Rectangle,
-    counter_digit_area: [iced::Rectangle; 3],
-    face_area: iced::Rectangle,
-    borders: Vec<iced::Rectangle>,
-    light_paths: Vec<canvas::Path>,
-    shadow_paths: Vec<canvas::Path>,
+    tool: EditorTool,
+    show_mines: bool,
+    board_area: BoardArea,
     cell_size: u32,
-    foreground_cache: canvas::Cache,
-    background_cache: canvas::Cache,
-    skin: skin::Skin,
+    cache: canvas::Cache,
+    skin: Arc<skin::Skin>,
     viewport: iced::Rectangle,
 }
 
 impl Editor {
-    pub fn new(config: &GlobalConfig, skin: skin::Skin) -> Self {
-        let mut this = Self {
-            board: Vec2D::new(config.board[0], config.board[1]),
+    pub fn new(width: usize, height: usize, board_area: BoardArea, cell_size: u32, skin: Arc<skin::Skin>) -> Self {
+        Self {
+            cell_contents: Vec2D::new(width.max(1), height.max(1)),
+            cell_states: Vec2D::new(width.max(1), height.max(1)),
             mines: 0,
-            editor_area: iced::Rectangle::default(),
-            top_area: iced::Rectangle::default(),
-            board_area: iced::Rectangle::default(),
-            counter_area: iced::Rectangle::default(),
-            counter_digit_area: [iced::Rectangle::default(); 3],
-            face_area: iced::Rectangle::default(),
-            borders: Vec::new(),
-            light_paths: Vec::new(),
-            shadow_paths: Vec::new(),
-            cell_size: config.cell_size,
-            foreground_cache: canvas::Cache::new(),
-            background_cache: canvas::Cache::new(),
+            tool: EditorTool::default(),
+            show_mines: true,
+            board_area,
+            cell_size,
+            cache: canvas::Cache::new(),
             skin,
             viewport: Default::default(),
-        };
-        this.calculate_areas();
-        this
+        }
     }
 
-    fn calculate_areas(&mut self) {
-        let mut top_area = iced::Rectangle {
-            x: self.skin.border.width,
-            y: self.skin.border.width,
-            width: self.board.dims().0 as f32 * self.cell_size as f32,
-            height: self.skin.top_area.height,
-        };
+    /// Edit the position of `board`.
+    pub fn load(&mut self, board: &dyn board::Board) {
+        debug!("Loading a {}x{} board into the editor", board.width(), board.height());
+        self.cell_contents = board.cell_contents().clone();
+        self.cell_states = board.cell_states().clone();
+        // Blasted mines are painted as closed ones
+        for state in self.cell_states.iter_mut() {
+            if *state == CellState::Blasted {
+                *state = CellState::Closed;
+            }
+        }
+        self.mines = self
+            .cell_contents
+            .iter()
+            .filter(|&&content| content == CellContent::Mine)
+            .count();
+        self.cache.clear();
+    }
 
-        let counter_offset = ((self.skin.top_area.height - self.skin.top_area.counter.height) / 2.0).floor();
-        let counter_border_width = ((self.skin.top_area.counter.height
-            - self.skin.top_area.counter.content_height
-            - self.skin.top_area.counter.content_gap * 2.0)
-            / 2.0)
-            .floor();
-        let counter_right_top;
-        let mut counter_area = iced::Rectangle {
-            x: top_area.x + counter_offset + counter_border_width,
-            y: top_area.y + counter_offset + counter_border_width,
-            width: self.skin.top_area.counter.content_width * 3.0 + self.skin.top_area.counter.content_gap * 6.0,
-            height: self.skin.top_area.counter.content_height + self.skin.top_area.counter.content_gap * 2.0,
-        };
-        let counter_digit_area;
-        if (counter_area.x + counter_area.width + counter_border_width + counter_offset) > (top_area.x + top_area.width)
-        {
-            debug!("Not enough space for counter, skipping");
-            trace!(
-                "counter_area.x = {}, counter_area.width = {}, counter_border_width = {}, counter_offset = {}, \
-                 top_area.x = {}, top_area.width = {}",
-                counter_area.x, counter_area.width, counter_border_width, counter_offset, top_area.x, top_area.width
-            );
-            counter_area = iced::Rectangle::default();
-            counter_digit_area = [iced::Rectangle::default(); 3];
-            counter_right_top = 0.0;
-        } else {
-            counter_digit_area = [
-                iced::Rectangle {
-                    x: counter_area.x + self.skin.top_area.counter.content_gap,
-                    y: counter_area.y + self.skin.top_area.counter.content_gap,
-                    width: self.skin.top_area.counter.content_width,
-                    height: self.skin.top_area.counter.content_height,
-                },
-                iced::Rectangle {
-                    x: counter_area.x
-                        + self.skin.top_area.counter.content_width
-                        + self.skin.top_area.counter.content_gap * 3.0,
-                    y: counter_area.y + self.skin.top_area.counter.content_gap,
-                    width: self.skin.top_area.counter.content_width,
-                    height: self.skin.top_area.counter.content_height,
-                },
-                iced::Rectangle {
-                    x: counter_area.x
-                        + self.skin.top_area.counter.content_width * 2.0
-                        + self.skin.top_area.counter.content_gap * 5.0,
-                    y: counter_area.y + self.skin.top_area.counter.content_gap,
-                    width: self.skin.top_area.counter.content_width,
-                    height: self.skin.top_area.counter.content_height,
-                },
-            ];
-            counter_right_top = counter_area.x + counter_area.width + counter_border_width * 2.0;
+    pub fn width(&self) -> usize {
+        self.cell_contents.dims().0
+    }
+
+    pub fn height(&self) -> usize {
+        self.cell_contents.dims().1
+    }
+
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    pub fn tool(&self) -> EditorTool {
+        self.tool
+    }
+
+    pub fn shows_mines(&self) -> bool {
+        self.show_mines
+    }
+
+    pub fn cell_contents(&self) -> &Vec2D<CellContent> {
+        &self.cell_contents
+    }
+
+    pub fn cell_states(&self) -> &Vec2D<CellState> {
+        &self.cell_states
+    }
+
+    /// The painted position as a board, to be solved or played.
+    pub fn board(&self) -> Option<board::StandardBoard> {
+        board::StandardBoard::import(
+            board::ImportPack {
+                cell_contents: self.cell_contents.clone(),
+                mines: self.mines,
+                start_position: None,
+                cell_states: Some(self.cell_states.clone()),
+                seed: None,
+            },
+            board::ChordMode::Standard,
+        )
+    }
+
+    pub fn resize(&mut self, cell_size: u32, board_area: BoardArea, skin: Arc<skin::Skin>) {
+        trace!("Editor resize: cell_size={}", cell_size);
+        self.cell_size = cell_size;
+        self.board_area = board_area;
+        self.skin = skin;
+        self.cache.clear();
+    }
+
+    fn cell_at(&self, pos: iced::Point) -> Option<(usize, usize)> {
+        let x = ((pos.x - self.board_area.game_area.x) / self.cell_size as f32).floor();
+        let y = ((pos.y - self.board_area.game_area.y) / self.cell_size as f32).floor();
+        if x < 0.0 || y < 0.0 || x as usize >= self.width() || y as usize >= self.height() {
+            return None;
         }
+        Some((x as usize, y as usize))
+    }
+
+    fn cell_position(&self, x: usize, y: usize) -> iced::Point {
+        iced::Point::new(
+            x as f32 * self.cell_size as f32 + self.board_area.game_area.x,
+            y as f32 * self.cell_size as f32 + self.board_area.game_area.y,
+        )
+    }
 
-        let face_offset = ((self.skin.top_area.height - self.skin.top_area.face.size) / 2.0).floor();
-        let face_area;
-        if (counter_right_top + self.skin.top_area.face.size + face_offset * 2.0) > top_area.width {
-            debug!("Not enough space for face, skipping");
-            face_area = iced::Rectangle::default();
-        } else if counter_right_top + self.skin.top_area.face.size / 2.0 + face_offset
-            < top_area.x + top_area.width / 2.0
-        {
-            debug!("Placing face in the center");
-            face_area = iced::Rectangle {
-                x: top_area.x + (top_area.width - self.skin.top_area.face.size) / 2.0,
-                y: top_area.y + face_offset,
-                width: self.skin.top_area.face.size,
-                height: self.skin.top_area.face.size,
-            };
+    /// Set the content of `(x, y)` and recount the numbers around it, opened ones included.
+    fn set_mine(&mut self, x: usize, y: usize, mine: bool) {
+        if (self.cell_contents[(x, y)] == CellContent::Mine) == mine {
+            return;
+        }
+        if mine {
+            self.mines += 1;
+            self.cell_contents[(x, y)] = CellContent::Mine;
         } else {
-            debug!("Placing face to the right of counter");
-            face_area = iced::Rectangle {
-                x: counter_right_top + face_offset,
-                y: top_area.y + face_offset,
-                width: self.skin.top_area.face.size,
-                height: self.skin.top_area.face.size,
-            };
+            self.mines -= 1;
+            self.cell_contents[(x, y)] = CellContent::Empty;
+        }
+        let (width, height) = self.cell_contents.dims();
+        for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                if self.cell_contents[(nx, ny)] == CellContent::Mine {
+                    continue;
+                }
+                let count = (ny.saturating_sub(1)..=(ny + 1).min(height - 1))
+                    .flat_map(|my| (nx.saturating_sub(1)..=(nx + 1).min(width - 1)).map(move |mx| (mx, my)))
+                    .filter(|&pos| self.cell_contents[pos] == CellContent::Mine)
+                    .count() as u8;
+                self.cell_contents[(nx, ny)] = if count == 0 {
+                    CellContent::Empty
+                } else {
+                    CellContent::Number(count)
+                };
+                if let CellState::Opening(_) = self.cell_states[(nx, ny)] {
+                    self.cell_states[(nx, ny)] = CellState::Opening(count);
+                }
+            }
         }
+    }
 
-        let game_area_offset = if counter_area == iced::Rectangle::default() && face_area == iced::Rectangle::default()
-        {
-            debug!("No top area, adjusting game area accordingly");
-            top_area = iced::Rectangle::default();
-            self.skin.border.width
-        } else {
-            top_area.height + self.skin.border.width * 2.0
-        };
-        let board_area = iced::Rectangle {
-            x: self.skin.border.width,
-            y: game_area_offset,
-            width: self.board.dims().0 as f32 * self.cell_size as f32,
-            height: self.board.dims().1 as f32 * self.cell_size as f32,
-        };
+    /// Returns whether the position changed.
+    fn paint(&mut self, x: usize, y: usize) -> bool {
+        let before = (self.cell_contents[(x, y)], self.cell_states[(x, y)]);
+        match self.tool {
+            EditorTool::Mine => {
+                if let CellState::Opening(_) = self.cell_states[(x, y)] {
+                    self.cell_states[(x, y)] = CellState::Closed;
+                }
+                self.set_mine(x, y, true);
+            },
+            EditorTool::Reveal => {
+                self.cell_states[(x, y)] = match self.cell_contents[(x, y)] {
+                    CellContent::Empty => CellState::Opening(0),
+                    CellContent::Number(n) => CellState::Opening(n),
+                    CellContent::Mine => return false,
+                };
+            },
+            EditorTool::Flag => self.cell_states[(x, y)] = CellState::Flagged,
+            EditorTool::Close => self.cell_states[(x, y)] = CellState::Closed,
+        }
+        before != (self.cell_contents[(x, y)], self.cell_states[(x, y)])
+    }
 
-        let game_area = iced::Rectangle {
-            x: 0.0,
-            y: 0.0,
-            width: self.board.dims().0 as f32 * self.cell_size as f32 + self.skin.border.width * 2.0,
-            height: board_area.y + board_area.height + self.skin.border.width,
-        };
+    /// Returns whether the position changed.
+    fn erase(&mut self, x: usize, y: usize) -> bool {
+        let before = (self.cell_contents[(x, y)], self.cell_states[(x, y)]);
+        self.cell_states[(x, y)] = CellState::Closed;
+        self.set_mine(x, y, false);
+        before != (self.cell_contents[(x, y)], self.cell_states[(x, y)])
+    }
 
-        let mut borders = vec![
-            iced::Rectangle {
-                x: 0.0,
-                y: 0.0,
-                width: self.skin.border.width,
-                height: game_area.height,
+    /// Returns whether the position changed.
+    pub fn update(&mut self, message: EditorMessage) -> bool {
+        trace!("EditorMessage received: {:?}", message);
+        match message {
+            EditorMessage::Paint { x, y } => {
+                if self.paint(x, y) {
+                    trace!("Painted {} at ({}, {})", self.tool, x, y);
+                    self.cache.clear();
+                    return true;
+                }
             },
-            iced::Rectangle {
-                x: 0.0,
-                y: 0.0,
-                width: game_area.width,
-                height: self.skin.border.width,
+            EditorMessage::Erase { x, y } => {
+                if self.erase(x, y) {
+                    trace!("Erased ({}, {})", x, y);
+                    self.cache.clear();
+                    return true;
+                }
             },
-            iced::Rectangle {
-                x: game_area.x + game_area.width - self.skin.border.width,
-                y: 0.0,
-                width: self.skin.border.width,
-                height: game_area.height,
+            EditorMessage::SetTool(tool) => {
+                debug!("Editor tool: {}", tool);
+                self.tool = tool;
             },
-            iced::Rectangle {
-                x: 0.0,
-                y: game_area.y + game_area.height - self.skin.border.width,
-                width: game_area.width,
-                height: self.skin.border.width,
+            EditorMessage::SetShowMines(show_mines) => {
+                debug!("Editor shows mines: {}", show_mines);
+                self.show_mines = show_mines;
+                self.cache.clear();
+            },
+            EditorMessage::ViewportChanged(viewport) => {
+                self.viewport = viewport;
+                self.cache.clear();
             },
-        ];
-
-        let light_shadow_width = self.skin.border.width / 4.0;
-        let main_light_path = canvas::Path::new(|p| {
-            p.move_to(game_area.position());
-            p.line_to(iced::Point::new(game_area.x + game_area.width, game_area.y));
-            p.line_to(iced::Point::new(
-                game_area.x + game_area.width - light_shadow_width,
-                game_area.y + light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                game_area.x + light_shadow_width,
-                game_area.y + light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                game_area.x + light_shadow_width,
-                game_area.y + game_area.height - light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(game_area.x, game_area.y + game_area.height));
-            p.close();
-        });
-        let main_shadow_path = canvas::Path::new(|p| {
-            p.move_to(iced::Point::new(game_area.x + game_area.width, game_area.y));
-            p.line_to(iced::Point::new(
-                game_area.x + game_area.width,
-                game_area.y + game_area.height,
-            ));
-            p.line_to(iced::Point::new(game_area.x, game_area.y + game_area.height));
-            p.line_to(iced::Point::new(
-                game_area.x + light_shadow_width,
-                game_area.y + game_area.height - light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                game_area.x + game_area.width - light_shadow_width,
-                game_area.y + game_area.height - light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                game_area.x + game_area.width - light_shadow_width,
-                game_area.y + light_shadow_width,
-            ));
-            p.close();
-        });
-        let board_light_path = canvas::Path::new(|p| {
-            p.move_to(iced::Point::new(board_area.x + board_area.width, board_area.y));
-            p.line_to(iced::Point::new(
-                board_area.x + board_area.width,
-                board_area.y + board_area.height,
-            ));
-            p.line_to(iced::Point::new(board_area.x, board_area.y + board_area.height));
-            p.line_to(iced::Point::new(
-                board_area.x - light_shadow_width,
-                board_area.y + board_area.height + light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                board_area.x + board_area.width + light_shadow_width,
-                board_area.y + board_area.height + light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                board_area.x + board_area.width + light_shadow_width,
-                board_area.y - light_shadow_width,
-            ));
-            p.close();
-        });
-        let board_shadow_path = canvas::Path::new(|p| {
-            p.move_to(board_area.position());
-            p.line_to(iced::Point::new(board_area.x + board_area.width, board_area.y));
-            p.line_to(iced::Point::new(
-                board_area.x + board_area.width + light_shadow_width,
-                board_area.y - light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                board_area.x - light_shadow_width,
-                board_area.y - light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(
-                board_area.x - light_shadow_width,
-                board_area.y + board_area.height + light_shadow_width,
-            ));
-            p.line_to(iced::Point::new(board_area.x, board_area.y + board_area.height));
-            p.close();
-        });
-
-        let mut light_paths = vec![main_light_path, board_light_path];
-        let mut shadow_paths = vec![main_shadow_path, board_shadow_path];
-
-        if top_area != iced::Rectangle::default() {
-            borders.push(iced::Rectangle {
-                x: 0.0,
-                y: top_area.y + top_area.height,
-                width: game_area.width,
-                height: self.skin.border.width,
-            });
-            let top_light_path = canvas::Path::new(|p| {
-                p.move_to(iced::Point::new(top_area.x + top_area.width, top_area.y));
-                p.line_to(iced::Point::new(
-                    top_area.x + top_area.width,
-                    top_area.y + top_area.height,
-                ));
-                p.line_to(iced::Point::new(top_area.x, top_area.y + top_area.height));
-                p.line_to(iced::Point::new(
-                    top_area.x - light_shadow_width,
-                    top_area.y + top_area.height + light_shadow_width,
-                ));
-                p.line_to(iced::Point::new(
-                    top_area.x + top_area.width + light_shadow_width,
-                    top_area.y + top_area.height + light_shadow_width,
-                ));
-                p.line_to(iced::Point::new(
-                    top_area.x + top_area.width + light_shadow_width,
-                    top_area.y - light_shadow_width,
-                ));
-                p.close();
-            });
-            let top_shadow_path = canvas::Path::new(|p| {
-                p.move_to(top_area.position());
-                p.line_to(iced::Point::new(top_area.x + top_area.width, top_area.y));
-                p.line_to(iced::Point::new(
-                    top_area.x + top_area.width + light_shadow_width,
-                    top_area.y - light_shadow_width,
-                ));
-                p.line_to(iced::Point::new(
-                    top_area.x - light_shadow_width,
-                    top_area.y - light_shadow_width,
-                ));
-                p.line_to(iced::Point::new(
-                    top_area.x - light_shadow_width,
-                    top_area.y + top_area.height + light_shadow_width,
-                ));
-                p.line_to(iced::Point::new(top_area.x, top_area.y + top_area.height));
-                p.close();
-            });
-            light_paths.push(top_light_path);
-            shadow_paths.push(top_shadow_path);
         }
+        false
+    }
 
-        if counter_area != iced::Rectangle::default() && counter_border_width > 0.0 {
-            let counter_light_path = canvas::Path::new(|p| {
-                p.move_to(iced::Point::new(counter_area.x + counter_area.width, counter_area.y));
-                p.line_to(iced::Point::new(
-                    counter_area.x + counter_area.width,
-                    counter_area.y + counter_area.height,
-                ));
-                p.line_to(iced::Point::new(counter_area.x, counter_area.y + counter_area.height));
-                p.line_to(iced::Point::new(
-                    counter_area.x - counter_border_width,
-                    counter_area.y + counter_area.height + counter_border_width,
-                ));
-                p.line_to(iced::Point::new(
-                    counter_area.x + counter_area.width + counter_border_width,
-                    counter_area.y + counter_area.height + counter_border_width,
-                ));
-                p.line_to(iced::Point::new(
-                    counter_area.x + counter_area.width + counter_border_width,
-                    counter_area.y - counter_border_width,
-                ));
-                p.close();
-            });
-            let counter_shadow_path = canvas::Path::new(|p| {
-                p.move_to(counter_area.position());
-                p.line_to(iced::Point::new(counter_area.x + counter_area.width, counter_area.y));
-                p.line_to(iced::Point::new(
-                    counter_area.x + counter_area.width + counter_border_width,
-                    counter_area.y - counter_border_width,
-                ));
-                p.line_to(iced::Point::new(
-                    counter_area.x - counter_border_width,
-                    counter_area.y - counter_border_width,
-                ));
-                p.line_to(iced::Point::new(
-                    counter_area.x - counter_border_width,
-                    counter_area.y + counter_area.height + counter_border_width,
-                ));
-                p.line_to(iced::Point::new(counter_area.x, counter_area.y + counter_area.height));
-                p.close();
-            });
-            light_paths.push(counter_light_path);
-            shadow_paths.push(counter_shadow_path);
-        }
+    pub fn view(&self) -> iced::Element<'_, EditorMessage> {
+        canvas::Canvas::new(self)
+            .width(self.board_area.canvas_area.width)
+            .height(self.board_area.canvas_area.height)
+            .into()
+    }
+}
 
-        self.editor_area = game_area;
-        self.top_area = top_area;
-        self.board_area = board_area;
-        self.counter_area = counter_area;
-        self.counter_digit_area = counter_digit_area;
-        self.face_area = face_area;
-        self.borders = borders;
-        self.light_paths = light_paths;
-        self.shadow_paths = shadow_paths;
+impl canvas::Program<EditorMessage> for Editor {
+    type State = PaintState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &iced::Event,
+        bounds: iced::Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> Option<canvas::Action<EditorMessage>> {
+        let iced::Event::Mouse(mouse_event) = event else {
+            return None;
+        };
+        let position = cursor.position_in(bounds).and_then(|pos| self.cell_at(pos));
+        let message = |state: PaintState| match (state, position) {
+            (PaintState::Painting(_), Some((x, y))) => Some(EditorMessage::Paint { x, y }),
+            (PaintState::Erasing(_), Some((x, y))) => Some(EditorMessage::Erase { x, y }),
+            _ => None,
+        };
+        match mouse_event {
+            iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left) if cursor.is_over(bounds) => {
+                *state = PaintState::Painting(position);
+                Some(message(*state).map_or_else(canvas::Action::capture, |message| {
+                    canvas::Action::publish(message).and_capture()
+                }))
+            },
+            iced::mouse::Event::ButtonPressed(iced::mouse::Button::Right) if cursor.is_over(bounds) => {
+                *state = PaintState::Erasing(position);
+                Some(message(*state).map_or_else(canvas::Action::capture, |message| {
+                    canvas::Action::publish(message).and_capture()
+                }))
+            },
+            iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left | iced::mouse::Button::Right) => {
+                *state = PaintState::Idle;
+                None
+            },
+            // Dragging paints every cell the cursor passes over
+            iced::mouse::Event::CursorMoved { .. } => {
+                let last = match state {
+                    PaintState::Painting(last) | PaintState::Erasing(last) => last,
+                    PaintState::Idle => return None,
+                };
+                if *last == position {
+                    return None;
+                }
+                *last = position;
+                message(*state).map(|message| canvas::Action::publish(message).and_capture())
+            },
+            _ => None,
+        }
     }
 
-    pub fn update(&mut self) {}
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        _theme: &iced::Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let geom = self.cache.draw(renderer, bounds.size(), |frame| {
+            if self.board_area.counter_area != iced::Rectangle::default() {
+                let mines = self.mines.min(999);
+                let digits = [mines / 100, (mines / 10) % 10, mines % 10];
+                for (rect, digit) in self.board_area.counter_digit_area.iter().zip(digits) {
+                    frame.draw_image(*rect, &self.skin.top_area.counter.digits[digit]);
+                }
+            }
+            if self.board_area.face_area != iced::Rectangle::default() {
+                frame.draw_image(self.board_area.face_area, &self.skin.top_area.face.normal);
+            }
 
-    pub fn view(&self) -> iced::Element<'_, EditorMessage> {
-        iced::widget::scrollable("todo").into()
+            // Only draw the cells inside the viewport, see `Game::draw`
+            let (width, height) = self.cell_contents.dims();
+            let cell_size_f32 = self.cell_size as f32;
+            let board_x_in_content = bounds.x + self.board_area.game_area.x;
+            let board_y_in_content = bounds.y + self.board_area.game_area.y;
+            let board_x_end = board_x_in_content + self.board_area.game_area.width;
+            let board_y_end = board_y_in_content + self.board_area.game_area.height;
+
+            let visible_x_start = self.viewport.x.max(board_x_in_content);
+            let visible_y_start = self.viewport.y.max(board_y_in_content);
+            let visible_x_end = (self.viewport.x + self.viewport.width).min(board_x_end);
+            let visible_y_end = (self.viewport.y + self.viewport.height).min(board_y_end);
+
+            if visible_x_end <= visible_x_start || visible_y_end <= visible_y_start {
+                trace!("Viewport doesn't intersect with board area, skipping cell rendering");
+                return;
+            }
+
+            let start_x = ((visible_x_start - board_x_in_content) / cell_size_f32).floor() as usize;
+            let start_y = ((visible_y_start - board_y_in_content) / cell_size_f32).floor() as usize;
+            let end_x = (((visible_x_end - board_x_in_content) / cell_size_f32).ceil() as usize).min(width);
+            let end_y = (((visible_y_end - board_y_in_content) / cell_size_f32).ceil() as usize).min(height);
+
+            for x in start_x..end_x {
+                for y in start_y..end_y {
+                    let is_mine = self.cell_contents[(x, y)] == CellContent::Mine;
+                    let img = match self.cell_states[(x, y)] {
+                        CellState::Closed if is_mine && self.show_mines => &self.skin.cell.mine.unflagged,
+                        CellState::Closed => &self.skin.cell.closed,
+                        CellState::Flagged if !is_mine && self.show_mines => &self.skin.cell.mine.wrong,
+                        CellState::Flagged => &self.skin.cell.mine.flagged,
+                        CellState::Opening(n @ 1..=8) => &self.skin.cell.numbers[(n - 1) as usize],
+                        CellState::Opening(_) => &self.skin.cell.opening,
+                        CellState::Blasted => &self.skin.cell.mine.blasted,
+                    };
+                    frame.draw_image(
+                        iced::Rectangle::new(self.cell_position(x, y), iced::Size::new(cell_size_f32, cell_size_f32)),
+                        img,
+                    );
+                }
+            }
+        });
+
+        vec![geom]
     }
 }
@@ -2,7 +2,6 @@ use iced::Task;
 use log::{debug, info, trace, warn};
 
 use crate::{
-    base::*,
     config::*,
     ui::{main_window::MainWindow, modal::ModalMessage, player::PlayerMessage, *},
 };
@@ -12,6 +11,9 @@ pub enum AppMessage {
     GetWindowId(Option<iced::window::Id>),
     Modal(ModalMessage),
     Player(PlayerMessage),
+    Analyzer(analyzer::AnalyzerMessage),
+    Statistics(statistics::StatisticsMessage),
+    SwitchBaseWindow(main_window::BaseWindow),
     CloseWindow(iced::window::Id),
     ActivateWindow,
 }
@@ -24,18 +26,15 @@ pub struct App {
     export: modal::export::ExportModal,
     import: modal::import::ImportModal,
     error: modal::error::ErrorModal,
+    settings: modal::settings::SettingsModal,
+    skin: modal::skin::SkinModal,
 }
 
 impl App {
     pub fn new() -> (Self, Task<AppMessage>) {
         let config = GlobalConfig::load().unwrap_or_else(|err| {
             warn!("Failed to load config: {}, using default config.", err);
-            GlobalConfig {
-                chord_mode: board::ChordMode::LeftClick,
-                skin: "WoM Light".to_string(),
-                cell_size: 24,
-                board: [30, 16, 99],
-            }
+            GlobalConfig::default()
         });
 
         let mut error = modal::error::ErrorModal::new();
@@ -47,8 +46,17 @@ impl App {
             })
             .ok();
 
+        let mut tasks = vec![iced::window::latest().map(AppMessage::GetWindowId)];
+        if main_window.is_some() {
+            // Falls back to generating the first board in no-guess mode
+            tasks.push(Task::done(AppMessage::Player(PlayerMessage::Save(
+                player::SaveMessage::RestoreAutosave,
+            ))));
+        }
+
         (
             Self {
+                settings: modal::settings::SettingsModal::new(config.key_bindings.clone()),
                 config,
                 id: None,
                 main_window,
@@ -56,8 +64,9 @@ impl App {
                 export: modal::export::ExportModal::new(),
                 import: modal::import::ImportModal::new(),
                 error,
+                skin: modal::skin::SkinModal::new(Vec::new(), Vec::new(), String::new()),
             },
-            iced::window::latest().map(AppMessage::GetWindowId),
+            Task::batch(tasks),
         )
     }
 
@@ -129,19 +138,37 @@ impl App {
                         debug!("Showing export modal");
                         self.current_modal = modal::Modal::ExportGame;
                     },
+                    player::RequestMessage::ShowSettingsModal => {
+                        debug!("Showing settings modal");
+                        self.settings = modal::settings::SettingsModal::new(self.config.key_bindings.clone());
+                        self.current_modal = modal::Modal::Settings;
+                    },
+                    player::RequestMessage::ShowSkinModal => {
+                        debug!("Showing skin modal");
+                        let (previews, errors) = self
+                            .main_window
+                            .as_ref()
+                            .map(|main_window| main_window.skin_previews())
+                            .unwrap_or_default();
+                        self.skin = modal::skin::SkinModal::new(previews, errors, self.config.skin.clone());
+                        self.current_modal = modal::Modal::Skin;
+                    },
                     player::RequestMessage::ShowErrorModal(err) => {
                         debug!("Showing error modal: {}", err);
                         self.error.error_message = err;
                         self.current_modal = modal::Modal::Error;
                     },
-                    player::RequestMessage::UpdateBoardArea(..) => {
+                    player::RequestMessage::PreviewSkin { .. } | player::RequestMessage::UpdateBoardArea(..) => {
                         if let Some(main_window) = &mut self.main_window {
                             return main_window.update(AppMessage::Player(PlayerMessage::Request(request)));
                         };
                     },
                 }
             },
-            AppMessage::Player(..) => {
+            AppMessage::Player(..)
+            | AppMessage::Analyzer(..)
+            | AppMessage::Statistics(..)
+            | AppMessage::SwitchBaseWindow(..) => {
                 if let Some(main_window) = &mut self.main_window {
                     return main_window.update(msg);
                 };
@@ -152,6 +179,37 @@ impl App {
                 debug!("Closing modal: {:?}", self.current_modal);
                 self.current_modal = modal::Modal::None;
             },
+            AppMessage::Modal(ModalMessage::Settings(msg)) => {
+                trace!("Handling settings modal message: {:?}", msg);
+                let close = matches!(msg, modal::settings::SettingsMessage::Close);
+                self.settings.update(msg);
+                if close {
+                    debug!("Closing settings modal");
+                    self.current_modal = modal::Modal::None;
+                    if self.config.key_bindings != self.settings.key_bindings {
+                        info!("Key bindings changed");
+                        self.config.key_bindings = self.settings.key_bindings.clone();
+                        if let Some(main_window) = &mut self.main_window {
+                            return main_window.update(AppMessage::Player(PlayerMessage::KeyBindingsChanged(
+                                self.config.key_bindings.clone(),
+                            )));
+                        }
+                    }
+                }
+            },
+            AppMessage::Modal(ModalMessage::Skin(msg)) => {
+                trace!("Handling skin modal message: {:?}", msg);
+                self.current_modal = modal::Modal::None;
+                if let modal::skin::SkinMessage::Selected(skin) = msg {
+                    info!("Switching to skin: {}", skin);
+                    return self.update(AppMessage::Player(PlayerMessage::Request(
+                        player::RequestMessage::RegenerateSkin {
+                            skin,
+                            cell_size: self.config.cell_size,
+                        },
+                    )));
+                }
+            },
             AppMessage::Modal(ModalMessage::Import(msg)) => {
                 trace!("Handling import modal message: {:?}", msg);
                 if let modal::import::ImportMessage::Confirm = msg {
@@ -192,6 +250,9 @@ impl App {
                 _ => {
                     debug!("Saving config on exit: {:?}", self.config);
                     _ = self.config.save();
+                    if let Some(main_window) = &self.main_window {
+                        main_window.autosave();
+                    }
                     return iced::exit();
                 },
             },
@@ -223,6 +284,16 @@ impl App {
                 self.error.view().map(ModalMessage::Error).map(AppMessage::Modal),
                 AppMessage::Modal(ModalMessage::Error(modal::error::ErrorMessage::Acknowledge)),
             ),
+            modal::Modal::Settings => App::modal(
+                base,
+                self.settings.view().map(ModalMessage::Settings).map(AppMessage::Modal),
+                AppMessage::Modal(ModalMessage::Settings(modal::settings::SettingsMessage::Close)),
+            ),
+            modal::Modal::Skin => App::modal(
+                base,
+                self.skin.view().map(ModalMessage::Skin).map(AppMessage::Modal),
+                AppMessage::Modal(ModalMessage::Skin(modal::skin::SkinMessage::Close)),
+            ),
             modal::Modal::None => base,
         }
     }
@@ -232,10 +303,22 @@ impl App {
     }
 
     pub fn subscriptions(&self) -> iced::Subscription<AppMessage> {
-        let mut subscriptions = Vec::with_capacity(3);
+        let mut subscriptions = Vec::with_capacity(5);
         subscriptions.push(iced::window::close_requests().map(AppMessage::CloseWindow));
         if let Some(main_window) = &self.main_window {
             subscriptions.push(main_window.subscriptions());
+            // Keys belong to the modal while one is shown
+            if matches!(self.current_modal, modal::Modal::None) {
+                subscriptions.push(main_window.keyboard_subscription());
+            }
+        }
+        if self.current_modal == modal::Modal::Settings && self.settings.is_recording() {
+            subscriptions.push(iced::keyboard::listen().filter_map(|event| match event {
+                iced::keyboard::Event::KeyPressed { key, modifiers, .. } => Some(AppMessage::Modal(
+                    ModalMessage::Settings(modal::settings::SettingsMessage::KeyPressed(key, modifiers)),
+                )),
+                _ => None,
+            }));
         }
         subscriptions.push(crate::single_instance::activation_subscription());
         iced::Subscription::batch(subscriptions)
@@ -8,6 +8,8 @@ pub struct BoardArea {
     pub game_area: iced::Rectangle,
     pub counter_area: iced::Rectangle,
     pub counter_digit_area: [iced::Rectangle; 3],
+    pub timer_area: iced::Rectangle,
+    pub timer_digit_area: [iced::Rectangle; 3],
     pub face_area: iced::Rectangle,
 }
 
@@ -46,57 +48,69 @@ impl BoardArea {
             counter_digit_area = [iced::Rectangle::default(); 3];
             counter_right_top = 0.0;
         } else {
-            counter_digit_area = [
-                iced::Rectangle {
-                    x: counter_area.x + skin.top_area.counter.content_gap,
-                    y: counter_area.y + skin.top_area.counter.content_gap,
-                    width: skin.top_area.counter.content_width,
-                    height: skin.top_area.counter.content_height,
-                },
-                iced::Rectangle {
-                    x: counter_area.x + skin.top_area.counter.content_width + skin.top_area.counter.content_gap * 3.0,
-                    y: counter_area.y + skin.top_area.counter.content_gap,
-                    width: skin.top_area.counter.content_width,
-                    height: skin.top_area.counter.content_height,
-                },
-                iced::Rectangle {
-                    x: counter_area.x
-                        + skin.top_area.counter.content_width * 2.0
-                        + skin.top_area.counter.content_gap * 5.0,
-                    y: counter_area.y + skin.top_area.counter.content_gap,
-                    width: skin.top_area.counter.content_width,
-                    height: skin.top_area.counter.content_height,
-                },
-            ];
+            counter_digit_area = Self::digit_areas(skin, counter_area);
             counter_right_top = counter_area.x + counter_area.width + counter_border_width * 2.0;
             trace!("counter area: {:?}", counter_area);
             trace!("counter digit areas: {:?}", counter_digit_area);
         }
 
+        // The timer mirrors the counter on the right side
+        let mut timer_area = iced::Rectangle {
+            x: top_area.x + top_area.width - counter_offset - counter_border_width - counter_area.width,
+            ..counter_area
+        };
+        let mut timer_left_top = timer_area.x - counter_border_width * 2.0;
+        if counter_area == iced::Rectangle::default() || timer_left_top < counter_right_top {
+            debug!("Not enough space for timer, skipping");
+            timer_area = iced::Rectangle::default();
+            timer_left_top = top_area.width;
+        }
+
         let face_offset = ((skin.top_area.height - skin.top_area.face.size) / 2.0).floor();
-        let face_area;
-        if (counter_right_top + skin.top_area.face.size + face_offset * 2.0) > top_area.width {
+        let place_face = |right_limit: f32| {
+            if (counter_right_top + skin.top_area.face.size + face_offset * 2.0) > right_limit {
+                None
+            } else if counter_right_top + skin.top_area.face.size / 2.0 + face_offset
+                < top_area.x + top_area.width / 2.0
+                && top_area.x + (top_area.width + skin.top_area.face.size) / 2.0 + face_offset <= right_limit
+            {
+                debug!("Placing face in the center");
+                Some(iced::Rectangle {
+                    x: top_area.x + (top_area.width - skin.top_area.face.size) / 2.0,
+                    y: top_area.y + face_offset,
+                    width: skin.top_area.face.size,
+                    height: skin.top_area.face.size,
+                })
+            } else {
+                debug!("Placing face to the right of counter");
+                Some(iced::Rectangle {
+                    x: counter_right_top + face_offset,
+                    y: top_area.y + face_offset,
+                    width: skin.top_area.face.size,
+                    height: skin.top_area.face.size,
+                })
+            }
+        };
+        let mut face_area = place_face(timer_left_top);
+        if face_area.is_none() && timer_area != iced::Rectangle::default() {
+            debug!("Not enough space for both face and timer, skipping timer");
+            timer_area = iced::Rectangle::default();
+            face_area = place_face(top_area.width);
+        }
+        let face_area = face_area.unwrap_or_else(|| {
             debug!("Not enough space for face, skipping");
-            face_area = iced::Rectangle::default();
-        } else if counter_right_top + skin.top_area.face.size / 2.0 + face_offset < top_area.x + top_area.width / 2.0 {
-            debug!("Placing face in the center");
-            face_area = iced::Rectangle {
-                x: top_area.x + (top_area.width - skin.top_area.face.size) / 2.0,
-                y: top_area.y + face_offset,
-                width: skin.top_area.face.size,
-                height: skin.top_area.face.size,
-            };
+            iced::Rectangle::default()
+        });
+        let timer_digit_area = if timer_area == iced::Rectangle::default() {
+            [iced::Rectangle::default(); 3]
         } else {
-            debug!("Placing face to the right of counter");
-            face_area = iced::Rectangle {
-                x: counter_right_top + face_offset,
-                y: top_area.y + face_offset,
-                width: skin.top_area.face.size,
-                height: skin.top_area.face.size,
-            };
-        }
+            trace!("timer area: {:?}", timer_area);
+            Self::digit_areas(skin, timer_area)
+        };
 
-        let game_area_offset = if counter_area == iced::Rectangle::default() && face_area == iced::Rectangle::default()
+        let game_area_offset = if counter_area == iced::Rectangle::default()
+            && timer_area == iced::Rectangle::default()
+            && face_area == iced::Rectangle::default()
         {
             debug!("No top area, adjusting game area accordingly");
             top_area = iced::Rectangle::default();
@@ -124,7 +138,20 @@ impl BoardArea {
             game_area: board_area,
             counter_area,
             counter_digit_area,
+            timer_area,
+            timer_digit_area,
             face_area,
         }
     }
+
+    /// Lay out the three digits of a counter-like area.
+    fn digit_areas(skin: &skin::Skin, area: iced::Rectangle) -> [iced::Rectangle; 3] {
+        let counter = &skin.top_area.counter;
+        [0.0, 1.0, 2.0].map(|i| iced::Rectangle {
+            x: area.x + counter.content_width * i + counter.content_gap * (i * 2.0 + 1.0),
+            y: area.y + counter.content_gap,
+            width: counter.content_width,
+            height: counter.content_height,
+        })
+    }
 }
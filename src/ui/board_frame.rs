@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::ui::{AppMessage, board_area::BoardArea, skin};
+use crate::ui::{board_area::BoardArea, skin};
 use iced::widget::canvas;
 
 pub struct BoardFrame {
@@ -215,56 +215,47 @@ impl BoardFrame {
             - skin.top_area.counter.content_gap * 2.0)
             / 2.0)
             .floor();
-        if board_area.counter_area != iced::Rectangle::default() && counter_border_width > 0.0 {
+        for counter_area in [board_area.counter_area, board_area.timer_area] {
+            if counter_area == iced::Rectangle::default() || counter_border_width <= 0.0 {
+                continue;
+            }
             let counter_light_path = canvas::Path::new(|p| {
-                p.move_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width,
-                    board_area.counter_area.y,
-                ));
-                p.line_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width,
-                    board_area.counter_area.y + board_area.counter_area.height,
-                ));
+                p.move_to(iced::Point::new(counter_area.x + counter_area.width, counter_area.y));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x,
-                    board_area.counter_area.y + board_area.counter_area.height,
+                    counter_area.x + counter_area.width,
+                    counter_area.y + counter_area.height,
                 ));
+                p.line_to(iced::Point::new(counter_area.x, counter_area.y + counter_area.height));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x - counter_border_width,
-                    board_area.counter_area.y + board_area.counter_area.height + counter_border_width,
+                    counter_area.x - counter_border_width,
+                    counter_area.y + counter_area.height + counter_border_width,
                 ));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width + counter_border_width,
-                    board_area.counter_area.y + board_area.counter_area.height + counter_border_width,
+                    counter_area.x + counter_area.width + counter_border_width,
+                    counter_area.y + counter_area.height + counter_border_width,
                 ));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width + counter_border_width,
-                    board_area.counter_area.y - counter_border_width,
+                    counter_area.x + counter_area.width + counter_border_width,
+                    counter_area.y - counter_border_width,
                 ));
                 p.close();
             });
             let counter_shadow_path = canvas::Path::new(|p| {
-                p.move_to(board_area.counter_area.position());
-                p.line_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width,
-                    board_area.counter_area.y,
-                ));
-                p.line_to(iced::Point::new(
-                    board_area.counter_area.x + board_area.counter_area.width + counter_border_width,
-                    board_area.counter_area.y - counter_border_width,
-                ));
+                p.move_to(counter_area.position());
+                p.line_to(iced::Point::new(counter_area.x + counter_area.width, counter_area.y));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x - counter_border_width,
-                    board_area.counter_area.y - counter_border_width,
+                    counter_area.x + counter_area.width + counter_border_width,
+                    counter_area.y - counter_border_width,
                 ));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x - counter_border_width,
-                    board_area.counter_area.y + board_area.counter_area.height + counter_border_width,
+                    counter_area.x - counter_border_width,
+                    counter_area.y - counter_border_width,
                 ));
                 p.line_to(iced::Point::new(
-                    board_area.counter_area.x,
-                    board_area.counter_area.y + board_area.counter_area.height,
+                    counter_area.x - counter_border_width,
+                    counter_area.y + counter_area.height + counter_border_width,
                 ));
+                p.line_to(iced::Point::new(counter_area.x, counter_area.y + counter_area.height));
                 p.close();
             });
             light_paths.push(counter_light_path);
@@ -281,7 +272,7 @@ impl BoardFrame {
         }
     }
 
-    pub fn view(&self) -> iced::Element<'_, AppMessage> {
+    pub fn view<Message: 'static>(&self) -> iced::Element<'_, Message> {
         canvas::Canvas::new(self)
             .width(self.board_area.canvas_area.width)
             .height(self.board_area.canvas_area.height)
@@ -289,7 +280,7 @@ impl BoardFrame {
     }
 }
 
-impl canvas::Program<AppMessage> for BoardFrame {
+impl<Message> canvas::Program<Message> for BoardFrame {
     type State = ();
 
     fn draw(
@@ -307,12 +298,11 @@ impl canvas::Program<AppMessage> for BoardFrame {
                 let top_area_background =
                     canvas::Path::rectangle(self.board_area.top_area.position(), self.board_area.top_area.size());
                 frame.fill(&top_area_background, self.skin.top_area.background_color);
-                if self.board_area.counter_area != iced::Rectangle::default() {
-                    let counter_background = canvas::Path::rectangle(
-                        self.board_area.counter_area.position(),
-                        self.board_area.counter_area.size(),
-                    );
-                    frame.fill(&counter_background, self.skin.top_area.counter.background_color);
+                for counter_area in [self.board_area.counter_area, self.board_area.timer_area] {
+                    if counter_area != iced::Rectangle::default() {
+                        let counter_background = canvas::Path::rectangle(counter_area.position(), counter_area.size());
+                        frame.fill(&counter_background, self.skin.top_area.counter.background_color);
+                    }
                 }
             }
             for border in &self.borders {
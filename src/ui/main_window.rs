@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use iced::Task;
-use log::{debug, error};
+use log::{debug, error, trace};
 
 use crate::{
     config::GlobalConfig,
@@ -12,6 +12,8 @@ use crate::{
 pub enum BaseWindow {
     #[default]
     Player,
+    Analyzer,
+    Statistics,
 }
 
 pub struct MainWindow {
@@ -20,16 +22,18 @@ pub struct MainWindow {
     theme: iced::Theme,
     board_frame: BoardFrame,
     player: player::Player,
+    analyzer: analyzer::Analyzer,
+    statistics: statistics::StatisticsPage,
     base_window: BaseWindow,
 }
 
 impl MainWindow {
     pub fn new(config: GlobalConfig) -> crate::error::Result<Self> {
-        let skin_manager = crate::utils::resource_path("skin")
+        let bundled = crate::utils::resource_path("skin")
             .inspect_err(|e| error!("Failed to get skin resource path: {}", e))
-            .and_then(|path| {
-                skin::SkinManager::new(path).inspect_err(|e| error!("Failed to initialize SkinManager: {}", e))
-            })?;
+            .ok();
+        let skin_manager = skin::SkinManager::new(bundled.into_iter().chain([skin::SkinManager::user_dir()]))
+            .inspect_err(|e| error!("Failed to initialize SkinManager: {}", e))?;
         let skin = Arc::new(
             skin_manager
                 .skins()
@@ -50,6 +54,7 @@ impl MainWindow {
         let board_area = BoardArea::calculate(&skin, config.cell_size, config.board[0], config.board[1]);
         let board_frame = BoardFrame::new(board_area, Arc::clone(&skin));
         let player = player::Player::new(config.clone(), board_area, Arc::clone(&skin));
+        let analyzer = analyzer::Analyzer::new(config.board[0], config.board[1], config.cell_size, Arc::clone(&skin));
 
         Ok(Self {
             skin_manager,
@@ -57,6 +62,8 @@ impl MainWindow {
             theme,
             board_frame,
             player,
+            analyzer,
+            statistics: statistics::StatisticsPage::new(),
             base_window: BaseWindow::default(),
         })
     }
@@ -77,12 +84,36 @@ impl MainWindow {
                         else {
                             return Task::none();
                         };
+                        self.theme = if skin.light {
+                            iced::Theme::Light
+                        } else {
+                            iced::Theme::Dark
+                        };
                         self.skin = Arc::new(skin);
-                        return self
-                            .player
-                            .update(player::PlayerMessage::UpdateSkin(Arc::clone(&self.skin)))
-                            .map(AppMessage::Player);
+                        return Task::batch([
+                            self.player
+                                .update(player::PlayerMessage::UpdateSkin(Arc::clone(&self.skin)))
+                                .map(AppMessage::Player),
+                            self.analyzer
+                                .update(analyzer::AnalyzerMessage::UpdateSkin {
+                                    skin: Arc::clone(&self.skin),
+                                    cell_size,
+                                })
+                                .map(AppMessage::Analyzer),
+                        ]);
+                    };
+                },
+                player::RequestMessage::PreviewSkin { cell_size } => {
+                    trace!("Previewing skin {} with cell size {}", self.skin.name, cell_size);
+                    let Some(skin_builder) = self.skin_manager.skins().get(&self.skin.name) else {
+                        error!("Skin '{}' not found.", self.skin.name);
+                        return Task::none();
                     };
+                    self.skin = Arc::new(skin_builder.preview(&self.skin, cell_size));
+                    return self
+                        .player
+                        .update(player::PlayerMessage::UpdateSkin(Arc::clone(&self.skin)))
+                        .map(AppMessage::Player);
                 },
                 player::RequestMessage::UpdateBoardArea(board_area) => {
                     debug!("Updating board area: {:?}", board_area);
@@ -93,15 +124,79 @@ impl MainWindow {
             AppMessage::Player(player_msg) => {
                 return self.player.update(player_msg).map(AppMessage::Player);
             },
+            AppMessage::Analyzer(analyzer::AnalyzerMessage::LoadGame) => {
+                debug!("Loading the current game into the analyzer");
+                return self.analyzer.load(self.player.board()).map(AppMessage::Analyzer);
+            },
+            AppMessage::Analyzer(analyzer_msg) => {
+                return self.analyzer.update(analyzer_msg).map(AppMessage::Analyzer);
+            },
+            AppMessage::Statistics(statistics_msg) => {
+                self.statistics.update(statistics_msg);
+            },
+            AppMessage::SwitchBaseWindow(base_window) => {
+                debug!("Switching to {:?}", base_window);
+                // Each base window has its own scrollable, start the new one from the top
+                let viewport = self.player.viewport();
+                let viewport = iced::Rectangle::new(iced::Point::ORIGIN, viewport.size());
+                self.base_window = base_window;
+                return match base_window {
+                    BaseWindow::Player => self
+                        .player
+                        .update(player::PlayerMessage::Game(player::GameMessage::ViewportChanged(
+                            viewport,
+                        )))
+                        .map(AppMessage::Player),
+                    BaseWindow::Analyzer => {
+                        self.analyzer.set_viewport(viewport);
+                        Task::none()
+                    },
+                    BaseWindow::Statistics => {
+                        // Games may have been played since the history was read
+                        self.statistics.reload();
+                        Task::none()
+                    },
+                };
+            },
             _ => {},
         }
         Task::none()
     }
 
+    fn view_tabs(&self, width: f32) -> iced::Element<'_, AppMessage> {
+        let tab = |label: &'static str, base_window: BaseWindow| {
+            iced::widget::button(
+                iced::widget::text(label)
+                    .size(14)
+                    .align_x(iced::alignment::Horizontal::Center),
+            )
+            .width(iced::FillPortion(1))
+            .padding([5, 2])
+            .style(if self.base_window == base_window {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            })
+            .on_press_maybe((self.base_window != base_window).then_some(AppMessage::SwitchBaseWindow(base_window)))
+        };
+        iced::widget::row![
+            tab("Game", BaseWindow::Player),
+            tab("Analyzer", BaseWindow::Analyzer),
+            tab("Stats", BaseWindow::Statistics),
+        ]
+        .spacing(4)
+        .padding(4)
+        .width(iced::Length::Fixed(width))
+        .into()
+    }
+
     pub fn view(&self) -> iced::Element<'_, AppMessage> {
         match self.base_window {
             BaseWindow::Player => iced::widget::scrollable(iced::widget::row![
-                self.player.view_sidebar(200.0).map(AppMessage::Player),
+                iced::widget::column![
+                    self.view_tabs(200.0),
+                    self.player.view_sidebar(200.0).map(AppMessage::Player)
+                ],
                 iced::widget::Stack::with_capacity(2)
                     .push(self.board_frame.view())
                     .push(self.player.view_game().map(AppMessage::Player)),
@@ -112,16 +207,86 @@ impl MainWindow {
                 vertical: Default::default(),
                 horizontal: Default::default(),
             })
+            .id(player::Player::SCROLLABLE)
             .on_scroll(|viewport| AppMessage::Player(player::PlayerMessage::Scrolled(viewport))),
+            BaseWindow::Analyzer => iced::widget::scrollable(iced::widget::row![
+                iced::widget::column![
+                    self.view_tabs(200.0),
+                    self.analyzer.view_sidebar(200.0).map(AppMessage::Analyzer)
+                ],
+                self.analyzer.view_board().map(AppMessage::Analyzer),
+            ])
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .direction(iced::widget::scrollable::Direction::Both {
+                vertical: Default::default(),
+                horizontal: Default::default(),
+            })
+            .on_scroll(|viewport| AppMessage::Analyzer(analyzer::AnalyzerMessage::Scrolled(viewport))),
+            BaseWindow::Statistics => iced::widget::scrollable(iced::widget::row![
+                iced::widget::column![
+                    self.view_tabs(200.0),
+                    self.statistics.view_sidebar(200.0).map(AppMessage::Statistics)
+                ],
+                self.statistics.view().map(AppMessage::Statistics),
+            ])
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .direction(iced::widget::scrollable::Direction::Both {
+                vertical: Default::default(),
+                horizontal: Default::default(),
+            }),
         }
         .into()
     }
 
+    /// Every skin built small enough to be shown side by side, sorted by name, and the skins that
+    /// failed to load or build.
+    pub fn skin_previews(&self) -> (Vec<skin::Skin>, Vec<skin::SkinLoadError>) {
+        let mut errors = self.skin_manager.errors().to_vec();
+        let mut previews: Vec<skin::Skin> = self
+            .skin_manager
+            .skins()
+            .values()
+            .filter_map(|builder| {
+                builder
+                    .build(modal::skin::SkinModal::PREVIEW_CELL_SIZE)
+                    .inspect_err(|e| {
+                        error!("Failed to build preview of skin '{}': {}", builder.config.name, e);
+                        errors.push(skin::SkinLoadError {
+                            dir: builder.dir.clone(),
+                            reason: e.to_string(),
+                        });
+                    })
+                    .ok()
+            })
+            .collect();
+        previews.sort_by(|a, b| a.name.cmp(&b.name));
+        (previews, errors)
+    }
+
+    /// Keep the current game for the next launch, see [`player::Player::autosave`].
+    pub fn autosave(&self) {
+        self.player.autosave();
+    }
+
     pub fn theme(&self) -> Option<iced::Theme> {
         Some(self.theme.clone())
     }
 
     pub fn subscriptions(&self) -> iced::Subscription<AppMessage> {
-        self.player.subscriptions().map(AppMessage::Player)
+        iced::Subscription::batch([
+            self.player.subscriptions().map(AppMessage::Player),
+            self.analyzer.subscriptions().map(AppMessage::Analyzer),
+        ])
+    }
+
+    /// Keys for the game, only while it is shown.
+    pub fn keyboard_subscription(&self) -> iced::Subscription<AppMessage> {
+        if self.base_window == BaseWindow::Player {
+            player::Player::keyboard_subscription().map(AppMessage::Player)
+        } else {
+            iced::Subscription::none()
+        }
     }
 }
@@ -1,6 +1,8 @@
 pub mod error;
 pub mod export;
 pub mod import;
+pub mod settings;
+pub mod skin;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Modal {
@@ -9,6 +11,8 @@ pub enum Modal {
     ImportGame,
     ExportGame,
     Error,
+    Settings,
+    Skin,
 }
 
 #[derive(Debug, Clone)]
@@ -16,4 +20,6 @@ pub enum ModalMessage {
     Import(import::ImportMessage),
     Export(export::ExportMessage),
     Error(error::ErrorMessage),
+    Settings(settings::SettingsMessage),
+    Skin(skin::SkinMessage),
 }
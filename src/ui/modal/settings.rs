@@ -0,0 +1,121 @@
+use crate::key_binding::{KeyAction, KeyBinding, KeyBindings};
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    /// Bind the next key pressed to the action.
+    Record(KeyAction),
+    KeyPressed(iced::keyboard::Key, iced::keyboard::Modifiers),
+    Unbind(KeyAction),
+    ResetDefaults,
+    Close,
+}
+
+pub struct SettingsModal {
+    pub key_bindings: KeyBindings,
+    /// The action waiting for a key, if any.
+    recording: Option<KeyAction>,
+    /// Why the last key pressed was not bound.
+    rejected: Option<String>,
+}
+
+impl SettingsModal {
+    pub fn new(key_bindings: KeyBindings) -> Self {
+        Self {
+            key_bindings,
+            recording: None,
+            rejected: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn update(&mut self, message: SettingsMessage) {
+        if !matches!(message, SettingsMessage::KeyPressed(..)) {
+            self.rejected = None;
+        }
+        match message {
+            SettingsMessage::Record(action) => self.recording = Some(action),
+            SettingsMessage::KeyPressed(key, modifiers) => {
+                let Some(action) = self.recording else {
+                    return;
+                };
+                // Escape cancels
+                if key == iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape) {
+                    self.recording = None;
+                    return;
+                }
+                // Wait for a key that is not a modifier
+                if let Some(binding) = KeyBinding::from_event(&key, modifiers) {
+                    self.rejected = self.key_bindings.set(action, binding).err();
+                    self.recording = None;
+                }
+            },
+            SettingsMessage::Unbind(action) => {
+                self.key_bindings.unbind(action);
+                self.recording = None;
+            },
+            SettingsMessage::ResetDefaults => {
+                self.key_bindings = KeyBindings::default();
+                self.recording = None;
+            },
+            SettingsMessage::Close => self.recording = None,
+        }
+    }
+
+    pub fn view(&self) -> iced::Element<'_, SettingsMessage> {
+        let rows = KeyAction::ALL.into_iter().map(|action| {
+            let binding = self.key_bindings.get(action);
+            let label = if self.recording == Some(action) {
+                "Press a key...".to_string()
+            } else if binding.is_unbound() {
+                "-".to_string()
+            } else {
+                binding.to_string()
+            };
+            iced::widget::row![
+                iced::widget::text(action.to_string())
+                    .size(16)
+                    .width(iced::FillPortion(1)),
+                iced::widget::button(iced::widget::text(label).align_x(iced::alignment::Horizontal::Center))
+                    .width(iced::FillPortion(1))
+                    .on_press(SettingsMessage::Record(action)),
+                iced::widget::button(iced::widget::text("Clear").align_x(iced::alignment::Horizontal::Center))
+                    .style(iced::widget::button::secondary)
+                    .on_press_maybe((!binding.is_unbound()).then_some(SettingsMessage::Unbind(action))),
+            ]
+            .spacing(10)
+            .align_y(iced::alignment::Vertical::Center)
+            .into()
+        });
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Key Bindings").size(20)),
+                iced::widget::column(rows).spacing(6),
+                iced::widget::text(
+                    "Arrows or hjkl move the keyboard cursor, Enter or D opens, F flags, S chords and Escape hides it."
+                )
+                .size(14),
+                self.rejected
+                    .as_ref()
+                    .map(|reason| iced::widget::text(reason).size(14).style(iced::widget::text::danger)),
+                iced::widget::row![
+                    iced::widget::button(iced::widget::text("Reset to Defaults"))
+                        .on_press(SettingsMessage::ResetDefaults)
+                        .padding([10, 20]),
+                    iced::widget::button(iced::widget::text("Done"))
+                        .on_press(SettingsMessage::Close)
+                        .padding([10, 20]),
+                ]
+                .spacing(10)
+            ]
+            .spacing(15),
+        )
+        .width(500)
+        .padding(20)
+        .style(iced::widget::container::rounded_box)
+        .into()
+    }
+}
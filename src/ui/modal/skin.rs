@@ -0,0 +1,118 @@
+use crate::ui::skin::{Skin, SkinLoadError, SkinManager};
+
+#[derive(Debug, Clone)]
+pub enum SkinMessage {
+    Selected(String),
+    Close,
+}
+
+/// Pick a skin from small previews of every skin found.
+pub struct SkinModal {
+    previews: Vec<Skin>,
+    errors: Vec<SkinLoadError>,
+    current: String,
+}
+
+impl SkinModal {
+    /// Cell size the previews are built with.
+    pub const PREVIEW_CELL_SIZE: u32 = 24;
+
+    pub fn new(previews: Vec<Skin>, errors: Vec<SkinLoadError>, current: String) -> Self {
+        Self {
+            previews,
+            errors,
+            current,
+        }
+    }
+
+    fn view_preview(skin: &Skin) -> iced::Element<'_, SkinMessage> {
+        let size = Self::PREVIEW_CELL_SIZE as f32;
+        let image = |handle: &iced::widget::image::Handle, size: f32| {
+            iced::widget::image(handle.clone()).width(size).height(size)
+        };
+        let cells = [
+            &skin.cell.closed,
+            &skin.cell.opening,
+            &skin.cell.numbers[0],
+            &skin.cell.numbers[1],
+            &skin.cell.numbers[2],
+            &skin.cell.mine.flagged,
+            &skin.cell.mine.unflagged,
+            &skin.cell.mine.blasted,
+        ]
+        .into_iter()
+        .map(|handle| image(handle, size).into());
+
+        iced::widget::row![
+            iced::widget::container(image(&skin.top_area.face.normal, skin.top_area.face.size))
+                .padding(4)
+                .style(move |_theme: &iced::Theme| iced::widget::container::Style {
+                    background: Some(skin.top_area.background_color.into()),
+                    ..Default::default()
+                }),
+            iced::widget::row(cells),
+        ]
+        .spacing(10)
+        .align_y(iced::alignment::Vertical::Center)
+        .into()
+    }
+
+    pub fn view(&self) -> iced::Element<'_, SkinMessage> {
+        let skins = self.previews.iter().map(|skin| {
+            let current = skin.name == self.current;
+            iced::widget::button(
+                iced::widget::column![
+                    iced::widget::text(if current {
+                        format!("{} (current)", skin.name)
+                    } else {
+                        skin.name.clone()
+                    })
+                    .size(16),
+                    Self::view_preview(skin),
+                ]
+                .spacing(6),
+            )
+            .width(iced::Fill)
+            .padding(8)
+            .style(if current {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            })
+            .on_press_maybe((!current).then(|| SkinMessage::Selected(skin.name.clone())))
+            .into()
+        });
+
+        let errors = (!self.errors.is_empty()).then(|| {
+            iced::widget::column![iced::widget::text("Failed to load:").size(16)]
+                .extend(self.errors.iter().map(|error| {
+                    iced::widget::text(error.to_string())
+                        .size(14)
+                        .style(iced::widget::text::danger)
+                        .into()
+                }))
+                .spacing(4)
+        });
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Skins").size(20)),
+                iced::widget::scrollable(iced::widget::column(skins).spacing(6).push(errors)).height(iced::Shrink),
+                iced::widget::text(format!(
+                    "Put your own skins in {}",
+                    SkinManager::user_dir().to_string_lossy()
+                ))
+                .size(14),
+                iced::widget::button(iced::widget::text("Done"))
+                    .on_press(SkinMessage::Close)
+                    .padding([10, 20]),
+            ]
+            .spacing(15),
+        )
+        .width(500)
+        .max_height(600)
+        .padding(20)
+        .style(iced::widget::container::rounded_box)
+        .into()
+    }
+}
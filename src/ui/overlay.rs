@@ -1,3 +1,5 @@
 mod solver_overlay;
+mod zini_overlay;
 
-pub use solver_overlay::{SolverOverlay, SolverOverlayMessage};
+pub use solver_overlay::{ProbabilityDisplay, SolverOverlay, SolverOverlayMessage};
+pub use zini_overlay::{ZiniAlgorithm, ZiniOverlay, ZiniOverlayMessage};
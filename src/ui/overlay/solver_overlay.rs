@@ -1,12 +1,59 @@
 use crate::{
     base::board,
-    engine::solver::{self, BoardSafety, Solver},
+    engine::solver::{self, BoardSafety, CellProbability, Solver},
     ui::board_area::BoardArea,
 };
 use iced::widget::canvas;
 use log::{debug, error, trace};
 use std::sync::Arc;
 
+/// The value of `CellProbability` shown on each closed cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProbabilityDisplay {
+    #[default]
+    MineProbability,
+    SecondSafety,
+    ProgressRate,
+    SolveRate,
+}
+
+impl ProbabilityDisplay {
+    pub const ALL: [ProbabilityDisplay; 4] = [
+        ProbabilityDisplay::MineProbability,
+        ProbabilityDisplay::SecondSafety,
+        ProbabilityDisplay::ProgressRate,
+        ProbabilityDisplay::SolveRate,
+    ];
+
+    /// Returns the displayed value and how risky it is (0.0 is the best, 1.0 the worst).
+    /// The value is NaN if it has not been computed for this cell.
+    fn value(self, probability: &CellProbability) -> (f32, f32) {
+        let value = match self {
+            ProbabilityDisplay::MineProbability => return (probability.mine_probability, probability.mine_probability),
+            ProbabilityDisplay::SecondSafety => probability.second_safety,
+            ProbabilityDisplay::ProgressRate => probability.progress_rate,
+            ProbabilityDisplay::SolveRate => probability.solve_rate,
+        };
+        (value, 1.0 - value)
+    }
+
+    /// Whether the displayed value is a lookahead metric, which the solver only computes on demand.
+    pub fn needs_lookahead(self) -> bool {
+        self != ProbabilityDisplay::MineProbability
+    }
+}
+
+impl std::fmt::Display for ProbabilityDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProbabilityDisplay::MineProbability => write!(f, "Mine Probability"),
+            ProbabilityDisplay::SecondSafety => write!(f, "Second Safety"),
+            ProbabilityDisplay::ProgressRate => write!(f, "Progress Rate"),
+            ProbabilityDisplay::SolveRate => write!(f, "Solve Rate"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SolverOverlayMessage {
     SolverCompleted(solver::error::Result<BoardSafety>),
@@ -14,11 +61,14 @@ pub enum SolverOverlayMessage {
     SetEnabled(bool),
     SetAdmitFlags(bool),
     SetLightSkin(bool),
+    SetDisplay(ProbabilityDisplay),
 }
 
 pub struct SolverOverlay {
     enabled: bool,
     solver: Arc<dyn Solver>,
+    /// Used instead of `solver` while a lookahead metric is displayed.
+    lookahead_solver: Arc<dyn Solver>,
     solver_result: Option<BoardSafety>,
     solver_admit_flags: bool,
     board_area: BoardArea,
@@ -26,13 +76,20 @@ pub struct SolverOverlay {
     cell_size: u32,
     cache: canvas::Cache,
     light_skin: bool,
+    display: ProbabilityDisplay,
 }
 
 impl SolverOverlay {
-    pub fn new<T: Solver + 'static>(solver: T, board_area: BoardArea, cell_size: u32) -> Self {
+    pub fn new<T: Solver + 'static, U: Solver + 'static>(
+        solver: T,
+        lookahead_solver: U,
+        board_area: BoardArea,
+        cell_size: u32,
+    ) -> Self {
         SolverOverlay {
             enabled: false,
             solver: Arc::new(solver),
+            lookahead_solver: Arc::new(lookahead_solver),
             solver_result: None,
             solver_admit_flags: false,
             board_area,
@@ -40,6 +97,7 @@ impl SolverOverlay {
             cell_size,
             cache: canvas::Cache::new(),
             light_skin: true,
+            display: ProbabilityDisplay::default(),
         }
     }
 
@@ -59,7 +117,11 @@ impl SolverOverlay {
         if !self.enabled {
             return iced::Task::none();
         }
-        let solver = Arc::clone(&self.solver);
+        let solver = if self.display.needs_lookahead() {
+            Arc::clone(&self.lookahead_solver)
+        } else {
+            Arc::clone(&self.solver)
+        };
         let mines = board.mines();
         let cell_states = board.cell_states().clone();
         let admit_flags = self.solver_admit_flags;
@@ -109,6 +171,11 @@ impl SolverOverlay {
                 self.light_skin = light_skin;
                 self.cache.clear();
             },
+            SolverOverlayMessage::SetDisplay(display) => {
+                trace!("Solver display: {}", display);
+                self.display = display;
+                self.cache.clear();
+            },
         }
     }
 
@@ -218,68 +285,108 @@ impl canvas::Program<SolverOverlayMessage> for SolverOverlay {
                             );
                         },
                         crate::engine::solver::CellSafety::Probability(cell_probability) => {
-                            // Calculate color based on mine probability (0.0-1.0 range)
-                            let text_color = if cell_probability.frontier {
-                                if self.light_skin {
-                                    iced::Color::from_rgb(
-                                        cell_probability.mine_probability * 0.65,
-                                        (1.0 - cell_probability.mine_probability) * 0.65,
-                                        0.0,
-                                    )
-                                } else {
-                                    iced::Color::from_rgb(
-                                        cell_probability.mine_probability,
-                                        1.0 - cell_probability.mine_probability,
-                                        0.0,
-                                    )
-                                }
-                            } else {
-                                iced::Color::from_rgb(0.5, 0.5, 0.5)
-                            };
-
-                            // Scale probability to 0.0-100.0 and format with up to 3 total digits
-                            let probability_percent = cell_probability.mine_probability * 100.0;
-
-                            // Format to show meaningful digits
-                            let probability_text = if probability_percent >= 10.0 {
-                                // For values >= 10, show 1 decimal place (e.g., 12.3, 46.0)
-                                format!("{:.1}", probability_percent)
-                            } else {
-                                // For values < 10, show 2 decimal places (e.g., 1.23, 0.01)
-                                format!("{:.2}", probability_percent)
-                            };
-
-                            // Draw text centered in the cell
                             let cell_pos = self.cell_position(x, y);
-                            let text_size = self.cell_size as f32 * 0.4; // Adjust text size relative to cell
+                            let (value, risk) = self.display.value(cell_probability);
 
-                            // Center the text in the cell by adjusting position
-                            // Text is drawn from baseline, so we offset it
-                            let text_position = iced::Point::new(
-                                cell_pos.x + self.cell_size as f32 * 0.5,
-                                cell_pos.y + self.cell_size as f32 * 0.5,
-                            );
+                            if !value.is_nan() {
+                                // Calculate color based on the risk (0.0-1.0 range)
+                                let text_color = if cell_probability.frontier {
+                                    if self.light_skin {
+                                        iced::Color::from_rgb(risk * 0.65, (1.0 - risk) * 0.65, 0.0)
+                                    } else {
+                                        iced::Color::from_rgb(risk, 1.0 - risk, 0.0)
+                                    }
+                                } else {
+                                    iced::Color::from_rgb(0.5, 0.5, 0.5)
+                                };
+
+                                // Scale value to 0.0-100.0 and format with up to 3 total digits
+                                let value_percent = value * 100.0;
 
-                            frame.fill_text(canvas::Text {
-                                content: probability_text,
-                                position: text_position,
-                                color: text_color,
-                                size: text_size.into(),
-                                font: iced::Font {
-                                    weight: iced::font::Weight::Bold,
+                                // Format to show meaningful digits
+                                let value_text = if value_percent >= 10.0 {
+                                    // For values >= 10, show 1 decimal place (e.g., 12.3, 46.0)
+                                    format!("{:.1}", value_percent)
+                                } else {
+                                    // For values < 10, show 2 decimal places (e.g., 1.23, 0.01)
+                                    format!("{:.2}", value_percent)
+                                };
+
+                                // Draw text centered in the cell
+                                let text_size = self.cell_size as f32 * 0.4; // Adjust text size relative to cell
+
+                                // Center the text in the cell by adjusting position
+                                // Text is drawn from baseline, so we offset it
+                                let text_position = iced::Point::new(
+                                    cell_pos.x + self.cell_size as f32 * 0.5,
+                                    cell_pos.y + self.cell_size as f32 * 0.5,
+                                );
+
+                                frame.fill_text(canvas::Text {
+                                    content: value_text,
+                                    position: text_position,
+                                    color: text_color,
+                                    size: text_size.into(),
+                                    font: iced::Font {
+                                        weight: iced::font::Weight::Bold,
+                                        ..Default::default()
+                                    },
+                                    max_width: self.cell_size as f32,
+                                    line_height: iced::widget::text::LineHeight::Relative(1.0),
+                                    align_x: iced::widget::text::Alignment::Center,
+                                    align_y: iced::alignment::Vertical::Center,
                                     ..Default::default()
-                                },
-                                max_width: self.cell_size as f32,
-                                line_height: iced::widget::text::LineHeight::Relative(1.0),
-                                align_x: iced::widget::text::Alignment::Center,
-                                align_y: iced::alignment::Vertical::Center,
-                                ..Default::default()
-                            });
+                                });
+                            }
+
+                            // Outline cells of a forced 50/50 so they stand out from ordinary guesses
+                            if cell_probability.half_chance {
+                                let line_width = (self.cell_size as f32 * 0.08).max(1.0);
+                                let inset = line_width * 0.5;
+                                let outline = canvas::Path::rectangle(
+                                    iced::Point::new(cell_pos.x + inset, cell_pos.y + inset),
+                                    iced::Size::new(
+                                        self.cell_size as f32 - line_width,
+                                        self.cell_size as f32 - line_width,
+                                    ),
+                                );
+                                frame.stroke(
+                                    &outline,
+                                    canvas::Stroke {
+                                        line_dash: canvas::LineDash {
+                                            segments: &[line_width * 1.5, line_width],
+                                            offset: 0,
+                                        },
+                                        ..canvas::Stroke::default()
+                                            .with_color(iced::Color::from_rgb(0.6, 0.2, 0.9))
+                                            .with_width(line_width)
+                                    },
+                                );
+                            }
                         },
                         _ => continue,
                     }
                 }
             }
+
+            // Highlight the recommended click
+            if let Some((x, y)) = board.suggestion()
+                && (start_x..end_x).contains(&x)
+                && (start_y..end_y).contains(&y)
+            {
+                let line_width = (self.cell_size as f32 * 0.12).max(2.0);
+                let cell_pos = self.cell_position(x, y);
+                let highlight = canvas::Path::rectangle(
+                    iced::Point::new(cell_pos.x + line_width * 0.5, cell_pos.y + line_width * 0.5),
+                    iced::Size::new(self.cell_size as f32 - line_width, self.cell_size as f32 - line_width),
+                );
+                frame.stroke(
+                    &highlight,
+                    canvas::Stroke::default()
+                        .with_color(iced::Color::from_rgb(0.1, 0.5, 1.0))
+                        .with_width(line_width),
+                );
+            }
         });
 
         vec![geom]
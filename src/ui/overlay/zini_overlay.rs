@@ -0,0 +1,277 @@
+use crate::{
+    base::{
+        Vec2D,
+        board::{self, CellContent, CellState},
+        statistics::{Click, Layout},
+        zini::{self, ZiniClick},
+    },
+    ui::{board_area::BoardArea, skin},
+};
+use iced::widget::canvas;
+use log::{debug, trace};
+use std::sync::Arc;
+
+/// The algorithm whose click sequence is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZiniAlgorithm {
+    #[default]
+    Greedy,
+    Human,
+}
+
+impl ZiniAlgorithm {
+    pub const ALL: [ZiniAlgorithm; 2] = [ZiniAlgorithm::Greedy, ZiniAlgorithm::Human];
+}
+
+impl std::fmt::Display for ZiniAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZiniAlgorithm::Greedy => write!(f, "ZiNi"),
+            ZiniAlgorithm::Human => write!(f, "Human ZiNi"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ZiniOverlayMessage {
+    Start,
+    Stop,
+    Tick,
+    SetAlgorithm(ZiniAlgorithm),
+}
+
+/// The click sequence being animated, together with the board it is played on.
+struct Sequence {
+    cell_contents: Vec2D<CellContent>,
+    cell_states: Vec2D<CellState>,
+    clicks: Vec<ZiniClick>,
+    step: usize,
+}
+
+/// Animates a ZiNi solution of the current layout over the game, one click per tick.
+///
+/// The sequence is played from a fresh board, so the real game underneath is left untouched; mouse
+/// input to it is blocked while the overlay is shown.
+pub struct ZiniOverlay {
+    algorithm: ZiniAlgorithm,
+    sequence: Option<Sequence>,
+    board_area: BoardArea,
+    viewport: iced::Rectangle,
+    cell_size: u32,
+    skin: Arc<skin::Skin>,
+    cache: canvas::Cache,
+}
+
+impl ZiniOverlay {
+    pub fn new(board_area: BoardArea, cell_size: u32, skin: Arc<skin::Skin>) -> Self {
+        ZiniOverlay {
+            algorithm: ZiniAlgorithm::default(),
+            sequence: None,
+            board_area,
+            viewport: iced::Rectangle::default(),
+            cell_size,
+            skin,
+            cache: canvas::Cache::new(),
+        }
+    }
+
+    fn cell_position(&self, x: usize, y: usize) -> iced::Point {
+        iced::Point::new(
+            x as f32 * self.cell_size as f32 + self.board_area.game_area.x,
+            y as f32 * self.cell_size as f32 + self.board_area.game_area.y,
+        )
+    }
+
+    pub fn set_viewport(&mut self, viewport: iced::Rectangle) {
+        self.viewport = viewport;
+        self.cache.clear();
+    }
+
+    pub fn resize(&mut self, cell_size: u32, board_area: BoardArea, skin: Arc<skin::Skin>) {
+        trace!("ZiNi overlay resize: cell_size={}", cell_size);
+        self.cell_size = cell_size;
+        self.board_area = board_area;
+        self.skin = skin;
+        self.cache.clear();
+    }
+
+    pub fn algorithm(&self) -> ZiniAlgorithm {
+        self.algorithm
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Whether there are clicks left to animate.
+    pub fn is_playing(&self) -> bool {
+        self.sequence
+            .as_ref()
+            .is_some_and(|sequence| sequence.step < sequence.clicks.len())
+    }
+
+    /// The number of clicks shown so far and the length of the sequence.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.sequence
+            .as_ref()
+            .map(|sequence| (sequence.step, sequence.clicks.len()))
+    }
+
+    /// Start animating the solution of the layout of `board`, whose game must be over since the
+    /// solution reveals every mine.
+    pub fn start(&mut self, board: &dyn board::Board) {
+        if !board.state().is_end() {
+            return;
+        }
+        let cell_contents = board.cell_contents().clone();
+        let layout = Layout::analyze(&cell_contents);
+        let clicks = match self.algorithm {
+            ZiniAlgorithm::Greedy => zini::greedy(&cell_contents, &layout),
+            ZiniAlgorithm::Human => zini::human(&cell_contents, &layout),
+        };
+        debug!("Showing {} solution with {} clicks", self.algorithm, clicks.len());
+        let (width, height) = cell_contents.dims();
+        self.sequence = Some(Sequence {
+            cell_contents,
+            cell_states: Vec2D::filled(width, height, CellState::Closed),
+            clicks,
+            step: 0,
+        });
+        self.cache.clear();
+    }
+
+    pub fn stop(&mut self) {
+        if self.sequence.take().is_some() {
+            debug!("Stopping the ZiNi solution");
+            self.cache.clear();
+        }
+    }
+
+    pub fn update(&mut self, message: ZiniOverlayMessage) {
+        trace!("ZiniOverlayMessage received: {:?}", message);
+        match message {
+            // Started by the player, which owns the board
+            ZiniOverlayMessage::Start => {},
+            ZiniOverlayMessage::Stop => self.stop(),
+            ZiniOverlayMessage::Tick => {
+                let Some(sequence) = &mut self.sequence else {
+                    return;
+                };
+                if let Some(&click) = sequence.clicks.get(sequence.step) {
+                    zini::apply(&sequence.cell_contents, &mut sequence.cell_states, click);
+                    sequence.step += 1;
+                    self.cache.clear();
+                }
+            },
+            ZiniOverlayMessage::SetAlgorithm(algorithm) => {
+                trace!("ZiNi algorithm: {}", algorithm);
+                self.algorithm = algorithm;
+            },
+        }
+    }
+
+    pub fn view(&self) -> iced::Element<'_, ZiniOverlayMessage> {
+        canvas::Canvas::new(self)
+            .width(self.board_area.canvas_area.width)
+            .height(self.board_area.canvas_area.height)
+            .into()
+    }
+}
+
+impl canvas::Program<ZiniOverlayMessage> for ZiniOverlay {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &iced::Event,
+        bounds: iced::Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> Option<canvas::Action<ZiniOverlayMessage>> {
+        // Keep the clicks away from the game underneath while the solution is shown
+        match event {
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(_) | iced::mouse::Event::ButtonReleased(_))
+                if self.is_active() && cursor.is_over(bounds) =>
+            {
+                Some(canvas::Action::capture())
+            },
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        _theme: &iced::Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let Some(sequence) = &self.sequence else {
+            return vec![];
+        };
+
+        let geom = self.cache.draw(renderer, bounds.size(), |frame| {
+            let (width, height) = sequence.cell_states.dims();
+            let cell_size_f32 = self.cell_size as f32;
+
+            // Only draw the cells inside the viewport, see `Game::draw`
+            let board_x_in_content = bounds.x + self.board_area.game_area.x;
+            let board_y_in_content = bounds.y + self.board_area.game_area.y;
+            let board_x_end = board_x_in_content + self.board_area.game_area.width;
+            let board_y_end = board_y_in_content + self.board_area.game_area.height;
+
+            let visible_x_start = self.viewport.x.max(board_x_in_content);
+            let visible_y_start = self.viewport.y.max(board_y_in_content);
+            let visible_x_end = (self.viewport.x + self.viewport.width).min(board_x_end);
+            let visible_y_end = (self.viewport.y + self.viewport.height).min(board_y_end);
+
+            if visible_x_end <= visible_x_start || visible_y_end <= visible_y_start {
+                trace!("Viewport doesn't intersect with board area, skipping cell rendering");
+                return;
+            }
+
+            let start_x = ((visible_x_start - board_x_in_content) / cell_size_f32).floor() as usize;
+            let start_y = ((visible_y_start - board_y_in_content) / cell_size_f32).floor() as usize;
+            let end_x = (((visible_x_end - board_x_in_content) / cell_size_f32).ceil() as usize).min(width);
+            let end_y = (((visible_y_end - board_y_in_content) / cell_size_f32).ceil() as usize).min(height);
+
+            for x in start_x..end_x {
+                for y in start_y..end_y {
+                    let img = match sequence.cell_states[(x, y)] {
+                        CellState::Closed => &self.skin.cell.closed,
+                        CellState::Flagged => &self.skin.cell.mine.flagged,
+                        CellState::Opening(n @ 1..=8) => &self.skin.cell.numbers[(n - 1) as usize],
+                        CellState::Opening(_) => &self.skin.cell.opening,
+                        CellState::Blasted => &self.skin.cell.mine.blasted,
+                    };
+                    frame.draw_image(
+                        iced::Rectangle::new(self.cell_position(x, y), iced::Size::new(cell_size_f32, cell_size_f32)),
+                        img,
+                    );
+                }
+            }
+
+            // Highlight the last click, coloured by its kind
+            if let Some(click) = sequence.step.checked_sub(1).map(|step| sequence.clicks[step]) {
+                let color = match click.click {
+                    Click::Left => iced::Color::from_rgb(0.1, 0.5, 1.0),
+                    Click::Right => iced::Color::from_rgb(0.9, 0.2, 0.2),
+                    Click::Chord => iced::Color::from_rgb(0.1, 0.7, 0.2),
+                };
+                let line_width = (cell_size_f32 * 0.12).max(2.0);
+                let cell_pos = self.cell_position(click.x, click.y);
+                let highlight = canvas::Path::rectangle(
+                    iced::Point::new(cell_pos.x + line_width * 0.5, cell_pos.y + line_width * 0.5),
+                    iced::Size::new(cell_size_f32 - line_width, cell_size_f32 - line_width),
+                );
+                frame.stroke(
+                    &highlight,
+                    canvas::Stroke::default().with_color(color).with_width(line_width),
+                );
+            }
+        });
+
+        vec![geom]
+    }
+}
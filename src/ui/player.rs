@@ -1,16 +1,30 @@
 use crate::{
-    base::{board, encode_decode},
+    base::{Vec2D, board, encode_decode, zini},
     config::*,
-    engine::solver,
+    daily,
+    engine::{generator, solver},
+    history,
+    key_binding::{KeyAction, KeyBinding, KeyBindings},
+    save,
     ui::{board_area::BoardArea, *},
 };
 use iced::{Function, Task};
 use log::{debug, error, info, trace};
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
 use tokio::sync::Mutex;
 
 mod game;
-pub use game::GameMessage;
+mod presets;
+mod recording;
+mod replay;
+mod saves;
+
+pub use game::{CursorAction, GameMessage};
+pub use presets::PresetMessage;
+pub use saves::SaveMessage;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextInputType {
@@ -24,7 +38,8 @@ pub enum TextInputType {
 pub enum ExportMessage {
     ButtonClicked,
     StartExport(encode_decode::EncodeType),
-    ExportCompleted(String),
+    /// The encoded board, `None` if it cannot be encoded in the chosen format.
+    ExportCompleted(Option<String>),
     TimerTick,
 }
 
@@ -38,11 +53,21 @@ pub enum ImportMessage {
 
 #[derive(Debug, Clone)]
 pub enum RequestMessage {
-    SyncConfigToApp(GlobalConfigUpdate),
-    RegenerateSkin { skin: String, cell_size: u32 },
+    SyncConfigToApp(Box<GlobalConfigUpdate>),
+    RegenerateSkin {
+        skin: String,
+        cell_size: u32,
+    },
     ShowImportModal,
     ShowExportModal,
     ShowErrorModal(String),
+    ShowSettingsModal,
+    ShowSkinModal,
+    /// Lay the skin out for the cell size without rasterising it again, see
+    /// [`skin::SkinBuilder::preview`].
+    PreviewSkin {
+        cell_size: u32,
+    },
     UpdateBoardArea(BoardArea),
 }
 
@@ -53,11 +78,32 @@ pub enum PlayerMessage {
     Game(GameMessage),
     TextInputChanged(TextInputType, String),
     CellSizeSubmit,
+    /// Check whether the cell size has settled after zooming.
+    ZoomTick,
     ChordModeToggled(bool),
+    FirstClickPolicySelected(board::FirstClickPolicy),
+    NoGuessToggled(bool),
+    /// The no-guess generation with the given id has finished.
+    BoardGenerated(u64),
+    /// The ZiNi of the layout `cell_contents` of a finished game has been computed.
+    ZiniComputed {
+        cell_contents: Vec2D<board::CellContent>,
+        greedy: usize,
+        human: usize,
+    },
+    /// Play the daily board of today.
+    Daily,
     Scrolled(iced::widget::scrollable::Viewport),
     Solver(overlay::SolverOverlayMessage),
+    Zini(overlay::ZiniOverlayMessage),
+    Replay(replay::ReplayMessage),
+    Save(SaveMessage),
+    Preset(PresetMessage),
     Export(ExportMessage),
     Import(ImportMessage),
+    /// A key that no widget has used, see [`Player::keyboard_subscription`].
+    KeyPressed(iced::keyboard::Key, iced::keyboard::Modifiers),
+    KeyBindingsChanged(KeyBindings),
 }
 
 #[derive(Debug, Clone)]
@@ -86,17 +132,63 @@ impl From<overlay::SolverOverlayMessage> for PlayerMessage {
     }
 }
 
+impl From<overlay::ZiniOverlayMessage> for PlayerMessage {
+    fn from(message: overlay::ZiniOverlayMessage) -> Self {
+        PlayerMessage::Zini(message)
+    }
+}
+
+/// A no-guess generation running in the background.
+struct Generation {
+    id: u64,
+    handle: iced::task::Handle,
+    /// Stops the generator between attempts, aborting the task only drops its result.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Generation {
+    fn cancel(self) {
+        debug!("Cancelling no-guess generation {}", self.id);
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.handle.abort();
+    }
+}
+
 pub struct Player {
     config: GlobalConfig,
     config_update: GlobalConfigUpdate,
     show_probabilities: bool,
     solver_admit_flags: bool,
+    solver_display: overlay::ProbabilityDisplay,
     skin: Arc<skin::Skin>,
     game: game::Game,
     board_to_import: Arc<Mutex<Option<Box<dyn board::Board + Send>>>>,
+    generated_board: Arc<Mutex<Option<Box<dyn board::Board + Send>>>>,
+    generation: Option<Generation>,
+    /// Id of the next no-guess generation, so that the result of a replaced one is recognised.
+    next_generation_id: u64,
+    /// Whether the ZiNi of a finished game is being computed.
+    computing_zini: bool,
     text_input_states: [String; 4],
     solver_overlay: overlay::SolverOverlay,
+    zini_overlay: overlay::ZiniOverlay,
+    replay: Option<replay::ReplayViewer>,
+    saves: saves::Saves,
+    presets: presets::Presets,
+    daily_history: daily::DailyHistory,
+    /// The date of the daily board being played, if any.
+    daily: Option<jiff::civil::Date>,
+    /// Whether the current game has been recorded, only its first end counts and not the ends
+    /// after Continue.
+    game_recorded: bool,
+    /// Whether the solver overlay has been shown during the current game.
+    solver_used: bool,
     viewport: iced::Rectangle,
+    /// The point of the canvas to keep under the cursor with the next skin, and where it is on
+    /// the board in cells.
+    zoom_anchor: Option<(iced::Point, iced::Vector)>,
+    /// When to rasterise the skin for the cell size reached by zooming.
+    zoom_settles_at: Option<std::time::Instant>,
     update_solver_in_progress: bool,
     update_solver_scheduled: bool,
     import_button_state: ImportButtonState,
@@ -107,6 +199,12 @@ impl Player {
     const TEXT_INPUT_UPPER: [usize; 4] = [1000, 1000, 1000000, 64];
     const TEXT_INPUT_LOWER: [usize; 4] = [1, 1, 1, 8];
     const TEXT_INPUT_DEFAULTS: [usize; 4] = [30, 16, 99, 24];
+    /// Change of the cell size by one line of the mouse wheel.
+    const ZOOM_FACTOR: f32 = 1.1;
+    /// How long the cell size must stay the same before the skin is rasterised for it.
+    const ZOOM_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+    /// The scrollable around the board, scrolled when zooming and panning.
+    pub const SCROLLABLE: iced::widget::Id = iced::widget::Id::new("player");
 
     pub fn new(config: GlobalConfig, board_area: BoardArea, skin: Arc<skin::Skin>) -> Self {
         let board = Box::new(board::StandardBoard::new(
@@ -114,6 +212,7 @@ impl Player {
             config.board[1],
             config.board[2],
             config.chord_mode,
+            config.first_click_policy,
         ));
         let game = game::Game::new(board, board_area, config.cell_size, Arc::clone(&skin));
         let text_input_states = [
@@ -122,19 +221,40 @@ impl Player {
             config.board[2].to_string(),
             config.cell_size.to_string(),
         ];
-        let mut solver_overlay = overlay::SolverOverlay::new(solver::default_engine(), board_area, config.cell_size);
+        let mut solver_overlay = overlay::SolverOverlay::new(
+            solver::default_engine(false),
+            solver::default_engine(true),
+            board_area,
+            config.cell_size,
+        );
         solver_overlay.update(overlay::SolverOverlayMessage::SetLightSkin(skin.light));
+        let zini_overlay = overlay::ZiniOverlay::new(board_area, config.cell_size, Arc::clone(&skin));
         Self {
             config,
             config_update: GlobalConfigUpdate::default(),
             show_probabilities: false,
             solver_admit_flags: false,
+            solver_display: overlay::ProbabilityDisplay::default(),
             skin,
             game,
             board_to_import: Arc::new(Mutex::new(None)),
+            generated_board: Arc::new(Mutex::new(None)),
+            generation: None,
+            next_generation_id: 0,
+            computing_zini: false,
             text_input_states,
             viewport: Default::default(),
+            zoom_anchor: None,
+            zoom_settles_at: None,
             solver_overlay,
+            zini_overlay,
+            replay: None,
+            saves: saves::Saves::default(),
+            presets: presets::Presets::default(),
+            daily_history: daily::DailyHistory::load(),
+            daily: None,
+            game_recorded: false,
+            solver_used: false,
             update_solver_in_progress: false,
             update_solver_scheduled: false,
             import_button_state: ImportButtonState::Import,
@@ -149,6 +269,9 @@ impl Player {
             board.height(),
             board.mines()
         );
+        if let Some(generation) = self.generation.take() {
+            generation.cancel();
+        }
         self.config.board = [board.width(), board.height(), board.mines()];
         let board_area = BoardArea::calculate(
             &self.skin,
@@ -173,9 +296,121 @@ impl Player {
             cell_size: self.config.cell_size,
             board_area,
         });
+        self.zini_overlay.stop();
+        self.replay = None;
+        self.daily = None;
+        self.game_recorded = false;
+        self.solver_used = self.show_probabilities;
+        self.zini_overlay
+            .resize(self.config.cell_size, board_area, Arc::clone(&self.skin));
         if let Some(task) = self.update_solver() {
             tasks.push(task);
         }
+        self.compute_zini(tasks);
+    }
+
+    /// Compute the ZiNi of the game if it has just ended, off the UI thread as it takes a while on
+    /// large boards.
+    fn compute_zini(&mut self, tasks: &mut Vec<Task<PlayerMessage>>) {
+        if self.computing_zini {
+            return;
+        }
+        let board = self.game.board();
+        let Some(layout) = self.game.statistics().pending_zini(board) else {
+            return;
+        };
+        debug!("Start computing ZiNi of the finished game");
+        let cell_contents = board.cell_contents().clone();
+        let layout = layout.clone();
+        self.computing_zini = true;
+        tasks.push(Task::perform(
+            async move {
+                let greedy = zini::greedy(&cell_contents, &layout).len();
+                let human = zini::human(&cell_contents, &layout).len();
+                PlayerMessage::ZiniComputed {
+                    cell_contents,
+                    greedy,
+                    human,
+                }
+            },
+            std::convert::identity,
+        ));
+    }
+
+    /// Continue `saved` in a new game, `false` if it is corrupted.
+    fn restore_game(&mut self, saved: &save::SavedGame, tasks: &mut Vec<Task<PlayerMessage>>) -> bool {
+        let Some(board) = saved.board() else {
+            return false;
+        };
+        let mut board: Box<dyn board::Board> = Box::new(board);
+        board.set_first_click_policy(self.config.first_click_policy);
+        self.config.chord_mode = saved.chord_mode();
+        self.config_update.chord_mode(self.config.chord_mode);
+        self.new_game(board, tasks);
+        self.game.restore(saved.elapsed(), saved.is_assisted());
+        true
+    }
+
+    /// Keep the current game for the next launch if it is still being played.
+    pub fn autosave(&self) {
+        let board = self.game.board();
+        if !matches!(board.state(), board::BoardState::InProgress { .. }) {
+            save::SavedGame::remove_autosave();
+            return;
+        }
+        if let Some(saved) = save::SavedGame::new(board, self.game.timer().elapsed(), self.game.is_assisted()) {
+            _ = saved.autosave();
+        }
+    }
+
+    /// Record the game that just ended in the history, and in the daily results if it is a daily board.
+    fn game_finished(&mut self) {
+        if self.game_recorded {
+            return;
+        }
+        self.game_recorded = true;
+        let board = self.game.board();
+        let statistics = self.game.statistics();
+        let record = history::GameRecord {
+            finished_at: jiff::Timestamp::now().to_string(),
+            width: board.width(),
+            height: board.height(),
+            mines: board.mines(),
+            time: self.game.timer().elapsed(),
+            won: board.state() == board::BoardState::Won,
+            left_clicks: statistics.left_clicks(),
+            right_clicks: statistics.right_clicks(),
+            chord_clicks: statistics.chord_clicks(),
+            bbbv: statistics.layout().map_or(0, |layout| layout.bbbv()),
+            solved_bbbv: statistics.solved_bbbv(),
+            chord_mode: board.chord_mode(),
+            solver: self.solver_used,
+            assisted: self.game.is_assisted(),
+            preset: BoardPreset::name_of([board.width(), board.height(), board.mines()], &self.config.presets),
+        };
+        debug!("Recording finished game: {:?}", record);
+
+        if let Some(date) = self.daily {
+            let attempt = daily::Attempt {
+                won: record.won,
+                assisted: record.assisted,
+                time: record.time,
+                bbbv: record.bbbv,
+                clicks: record.clicks(),
+            };
+            debug!("Recording daily attempt of {}: {:?}", date, attempt);
+            _ = self.daily_history.record(date, attempt);
+        }
+
+        _ = history::append(record);
+    }
+
+    pub fn board(&self) -> &dyn board::Board {
+        self.game.board()
+    }
+
+    pub fn viewport(&self) -> iced::Rectangle {
+        self.viewport
     }
 
     fn update_solver(&mut self) -> Option<Task<PlayerMessage>> {
@@ -196,6 +431,120 @@ impl Player {
         None
     }
 
+    /// Whether an import, export or generation is running, which disables most controls.
+    fn is_busy(&self) -> bool {
+        matches!(self.export_button_state, ExportButtonState::Exporting)
+            || matches!(self.import_button_state, ImportButtonState::Importing)
+            || self.generation.is_some()
+    }
+
+    /// The message of the control bound to `action`, `None` if the control is disabled.
+    fn key_action(&self, action: KeyAction) -> Option<PlayerMessage> {
+        let state = self.game.board().state();
+        let started = !matches!(state, board::BoardState::NotStarted);
+        let enabled = !self.is_busy();
+        match action {
+            KeyAction::NewGame => enabled.then_some(PlayerMessage::Game(GameMessage::FaceClicked)),
+            KeyAction::Replay => (enabled && started).then_some(PlayerMessage::Game(GameMessage::Replay)),
+            KeyAction::Continue => (enabled && matches!(state, board::BoardState::Lost { .. }))
+                .then_some(PlayerMessage::Game(GameMessage::Continue)),
+            KeyAction::ToggleProbability => Some(PlayerMessage::Solver(overlay::SolverOverlayMessage::SetEnabled(
+                !self.show_probabilities,
+            ))),
+            KeyAction::ToggleAdmitFlags => {
+                self.show_probabilities
+                    .then_some(PlayerMessage::Solver(overlay::SolverOverlayMessage::SetAdmitFlags(
+                        !self.solver_admit_flags,
+                    )))
+            },
+            KeyAction::Import => enabled.then_some(PlayerMessage::Import(ImportMessage::ButtonClicked)),
+            KeyAction::Export => (enabled && started).then_some(PlayerMessage::Export(ExportMessage::ButtonClicked)),
+            KeyAction::Undo => {
+                (enabled && self.game.board().can_undo()).then_some(PlayerMessage::Game(GameMessage::Undo))
+            },
+            KeyAction::Redo => {
+                (enabled && self.game.board().can_redo()).then_some(PlayerMessage::Game(GameMessage::Redo))
+            },
+            KeyAction::ZoomIn => Some(PlayerMessage::Game(GameMessage::Zoom {
+                lines: 1.0,
+                anchor: None,
+            })),
+            KeyAction::ZoomOut => Some(PlayerMessage::Game(GameMessage::Zoom {
+                lines: -1.0,
+                anchor: None,
+            })),
+        }
+    }
+
+    /// Show the board `lines` of the mouse wheel bigger or smaller, keeping `anchor` of the canvas
+    /// in place.
+    ///
+    /// The skin is only scaled until the cell size settles, rasterising it is too slow to follow
+    /// the wheel.
+    fn zoom(&mut self, lines: f32, anchor: Option<iced::Point>, tasks: &mut Vec<Task<PlayerMessage>>) {
+        if lines == 0.0 {
+            return;
+        }
+        let index = TextInputType::CellSize as usize;
+        let current = self.game.cell_size() as f32;
+        let target = (current * Self::ZOOM_FACTOR.powf(lines)).round();
+        // Small cells would not change with a single line otherwise
+        let target = if lines > 0.0 {
+            target.max(current + 1.0)
+        } else {
+            target.min(current - 1.0)
+        };
+        let cell_size = (target.max(0.0) as usize).clamp(Self::TEXT_INPUT_LOWER[index], Self::TEXT_INPUT_UPPER[index]);
+        if cell_size == self.game.cell_size() as usize {
+            return;
+        }
+        debug!("Zooming from cell size {} to {}", current, cell_size);
+        let game_area = self.game.board_area().game_area;
+        self.zoom_anchor = anchor.map(|anchor| (anchor, (anchor - game_area.position()) * (1.0 / current)));
+        self.config.cell_size = cell_size as u32;
+        self.text_input_states[index] = cell_size.to_string();
+        self.zoom_settles_at = Some(std::time::Instant::now() + Self::ZOOM_DEBOUNCE);
+        tasks.push(Task::done(PlayerMessage::Request(RequestMessage::PreviewSkin {
+            cell_size: self.config.cell_size,
+        })));
+    }
+
+    /// Generate a no-guess board of the configured size in the background, replacing any
+    /// generation already running.
+    fn generate_no_guess(&mut self, tasks: &mut Vec<Task<PlayerMessage>>) {
+        if let Some(generation) = self.generation.take() {
+            generation.cancel();
+        }
+        let [width, height, mines] = self.config.board;
+        debug!(
+            "Start generating no-guess board: {}x{} with {} mines",
+            width, height, mines
+        );
+        let generated_board = Arc::clone(&self.generated_board);
+        let import = self.boxed_import(board::StandardBoard::import);
+        let id = self.next_generation_id;
+        self.next_generation_id += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (task, handle) = Task::perform(
+            {
+                let cancelled = Arc::clone(&cancelled);
+                async move {
+                    let board = generator::no_guess(width, height, mines, &cancelled).and_then(import);
+                    let mut lock = generated_board.lock().await;
+                    // The generation replacing this one owns the slot now
+                    if !cancelled.load(Ordering::Relaxed) {
+                        *lock = board;
+                    }
+                    PlayerMessage::BoardGenerated(id)
+                }
+            },
+            std::convert::identity,
+        )
+        .abortable();
+        self.generation = Some(Generation { id, handle, cancelled });
+        tasks.push(task);
+    }
+
     fn boxed_import<T: board::Board + Send + 'static>(
         &self,
         import: impl Fn(board::ImportPack, board::ChordMode) -> Option<T> + 'static,
@@ -222,6 +571,18 @@ impl Player {
                     tasks.push(Task::done(PlayerMessage::Request(RequestMessage::UpdateBoardArea(
                         board_area,
                     ))));
+                    // Keep the anchor of the zoom under the cursor
+                    if let Some((anchor, cells)) = self.zoom_anchor.take() {
+                        let moved = board_area.game_area.position() + cells * self.config.cell_size as f32;
+                        let offset = moved - anchor;
+                        tasks.push(iced::widget::operation::scroll_by(
+                            Self::SCROLLABLE,
+                            iced::widget::operation::AbsoluteOffset {
+                                x: offset.x,
+                                y: offset.y,
+                            },
+                        ));
+                    }
                     self.skin = skin;
                     self.game.update(GameMessage::Resize {
                         cell_size: self.config.cell_size,
@@ -232,14 +593,70 @@ impl Player {
                         cell_size: self.config.cell_size,
                         board_area,
                     });
+                    self.zini_overlay
+                        .resize(self.config.cell_size, board_area, Arc::clone(&self.skin));
+                    if let Some(replay) = &mut self.replay {
+                        replay.update(replay::ReplayMessage::Game(GameMessage::Resize {
+                            cell_size: self.config.cell_size,
+                            board_area,
+                            skin: Arc::clone(&self.skin),
+                        }));
+                    }
+                },
+                PlayerMessage::Game(GameMessage::Zoom { lines, anchor }) => {
+                    self.zoom(lines, anchor, &mut tasks);
+                },
+                PlayerMessage::Game(GameMessage::Pan(offset)) => {
+                    tasks.push(iced::widget::operation::scroll_by(
+                        Self::SCROLLABLE,
+                        iced::widget::operation::AbsoluteOffset {
+                            x: offset.x,
+                            y: offset.y,
+                        },
+                    ));
+                },
+                PlayerMessage::ZoomTick => {
+                    if self
+                        .zoom_settles_at
+                        .is_some_and(|settles_at| std::time::Instant::now() >= settles_at)
+                    {
+                        debug!("Zoom settled at cell size {}", self.config.cell_size);
+                        self.zoom_settles_at = None;
+                        tasks.push(self.update(PlayerMessage::CellSizeSubmit));
+                    }
                 },
                 PlayerMessage::Game(msg) => {
                     trace!("Handling GameMessage: {:?}", msg);
                     let is_face_clicked = matches!(msg, GameMessage::FaceClicked);
 
+                    // The board is about to be replaced by the one being generated
+                    if self.generation.is_some()
+                        && matches!(
+                            msg,
+                            GameMessage::Board(..)
+                                | GameMessage::Cursor(..)
+                                | GameMessage::Continue
+                                | GameMessage::Replay
+                                | GameMessage::Undo
+                                | GameMessage::Redo
+                        )
+                    {
+                        debug!("Ignoring board input while generating a no-guess board");
+                        break 'out;
+                    }
+
                     if let GameMessage::ViewportChanged(viewport) = msg {
                         self.viewport = viewport;
                         self.solver_overlay.set_viewport(viewport);
+                        self.zini_overlay.set_viewport(viewport);
+                        if let Some(replay) = &mut self.replay {
+                            replay.update(replay::ReplayMessage::Game(GameMessage::ViewportChanged(viewport)));
+                        }
+                    }
+
+                    if is_face_clicked && self.config.no_guess {
+                        self.generate_no_guess(&mut tasks);
+                        break 'out;
                     }
 
                     if is_face_clicked {
@@ -256,6 +673,7 @@ impl Player {
                                     self.config.board[1],
                                     self.config.board[2],
                                     self.config.chord_mode,
+                                    self.config.first_click_policy,
                                 )),
                                 &mut tasks,
                             );
@@ -263,8 +681,30 @@ impl Player {
                         }
                     }
 
+                    let is_replay = matches!(msg, GameMessage::Replay);
+                    let was_end = self.game.board().state().is_end();
                     let should_update_solver = self.game.update(msg);
+                    if !was_end && self.game.board().state().is_end() {
+                        self.game_finished();
+                    }
+                    self.compute_zini(&mut tasks);
+                    // Playing the same board again is another attempt
+                    if is_replay || is_face_clicked {
+                        self.game_recorded = false;
+                        self.solver_used = self.show_probabilities;
+                    }
+                    // The board changed under the solution or the replay being shown
+                    if should_update_solver {
+                        self.zini_overlay.stop();
+                        self.replay = None;
+                    }
+                    // The solution reveals every mine, so it is only shown once the game is over
+                    if !self.game.board().state().is_end() {
+                        self.zini_overlay.stop();
+                    }
                     if is_face_clicked {
+                        // The board has been reset to a random layout
+                        self.daily = None;
                         self.config.board = [
                             self.game.board().width(),
                             self.game.board().height(),
@@ -331,6 +771,79 @@ impl Player {
                     debug!("Chord mode toggled: {:?}", self.config.chord_mode);
                     self.game.update(GameMessage::ChordModeChanged(self.config.chord_mode));
                 },
+                PlayerMessage::FirstClickPolicySelected(policy) => {
+                    debug!("First click policy selected: {:?}", policy);
+                    self.config.first_click_policy = policy;
+                    self.config_update.first_click_policy(policy);
+                    self.game.update(GameMessage::FirstClickPolicyChanged(policy));
+                },
+                PlayerMessage::NoGuessToggled(enabled) => {
+                    debug!("No-guess mode toggled: {}", enabled);
+                    self.config.no_guess = enabled;
+                    self.config_update.no_guess(enabled);
+                },
+                PlayerMessage::BoardGenerated(id) => {
+                    if self.generation.as_ref().is_none_or(|generation| generation.id != id) {
+                        debug!("Dropping the result of replaced no-guess generation {}", id);
+                        break 'out;
+                    }
+                    debug!("No-guess generation completed, retrieving board");
+                    self.generation = None;
+                    let board = self.generated_board.blocking_lock().take();
+                    match board {
+                        Some(board) => self.new_game(board, &mut tasks),
+                        None => {
+                            error!("Failed to generate no-guess board");
+                            tasks.push(Task::done(PlayerMessage::Request(RequestMessage::ShowErrorModal(
+                                "Failed to generate a no-guess board in time, try fewer mines".to_string(),
+                            ))));
+                        },
+                    }
+                },
+                PlayerMessage::ZiniComputed {
+                    cell_contents,
+                    greedy,
+                    human,
+                } => {
+                    self.computing_zini = false;
+                    // The game may have been replaced while computing
+                    if self.game.board().cell_contents() == &cell_contents {
+                        debug!("ZiNi computed: {} greedy, {} human", greedy, human);
+                        self.game.set_zini(greedy, human);
+                    }
+                    self.compute_zini(&mut tasks);
+                },
+                PlayerMessage::Daily => {
+                    let date = daily::today();
+                    info!("Starting the daily board of {}", date);
+                    let Some(board) =
+                        board::StandardBoard::import(daily::board_seed(date).import_pack(), self.config.chord_mode)
+                    else {
+                        error!("Failed to create the daily board of {}", date);
+                        break 'out;
+                    };
+                    self.new_game(Box::new(board), &mut tasks);
+                    self.daily = Some(date);
+                },
+                PlayerMessage::KeyPressed(key, modifiers) => {
+                    let action = KeyBinding::from_event(&key, modifiers)
+                        .and_then(|binding| self.config.key_bindings.action_of(&binding));
+                    if let Some(action) = action {
+                        debug!("Key bound to {:?} pressed", action);
+                        if let Some(message) = self.key_action(action) {
+                            tasks.push(self.update(message));
+                        }
+                    } else if !modifiers.command()
+                        && !modifiers.alt()
+                        && let Some(action) = CursorAction::from_key(key.as_ref())
+                    {
+                        tasks.push(self.update(PlayerMessage::Game(GameMessage::Cursor(action))));
+                    }
+                },
+                PlayerMessage::KeyBindingsChanged(key_bindings) => {
+                    debug!("Key bindings updated");
+                    self.config.key_bindings = key_bindings;
+                },
                 PlayerMessage::Scrolled(viewport) => {
                     trace!("Scrolled event received");
                     let absolute_offset = viewport.absolute_offset();
@@ -345,6 +858,10 @@ impl Player {
                     self.viewport = viewport_rect;
                     self.game.update(GameMessage::ViewportChanged(viewport_rect));
                     self.solver_overlay.set_viewport(self.viewport);
+                    self.zini_overlay.set_viewport(self.viewport);
+                    if let Some(replay) = &mut self.replay {
+                        replay.update(replay::ReplayMessage::Game(GameMessage::ViewportChanged(viewport_rect)));
+                    }
                 },
                 PlayerMessage::Solver(msg) => {
                     trace!("Solver message received: {:?}", msg);
@@ -352,6 +869,7 @@ impl Player {
                         overlay::SolverOverlayMessage::SetEnabled(true) => {
                             debug!("Solver enabled");
                             self.show_probabilities = true;
+                            self.solver_used = true;
                             self.solver_overlay.update(msg);
                             if let Some(task) = self.update_solver() {
                                 tasks.push(task);
@@ -372,6 +890,15 @@ impl Player {
                                 tasks.push(task);
                             }
                         },
+                        overlay::SolverOverlayMessage::SetDisplay(display) => {
+                            debug!("Solver display changed: {}", display);
+                            let recalculate = display.needs_lookahead() && !self.solver_display.needs_lookahead();
+                            self.solver_display = *display;
+                            self.solver_overlay.update(msg);
+                            if recalculate && let Some(task) = self.update_solver() {
+                                tasks.push(task);
+                            }
+                        },
                         overlay::SolverOverlayMessage::SolverCompleted(..) => {
                             self.solver_overlay.update(msg);
                             self.update_solver_in_progress = false;
@@ -386,6 +913,104 @@ impl Player {
                         _ => self.solver_overlay.update(msg),
                     }
                 },
+                PlayerMessage::Zini(msg) => {
+                    trace!("ZiNi message received: {:?}", msg);
+                    match msg {
+                        overlay::ZiniOverlayMessage::Start => self.zini_overlay.start(self.game.board()),
+                        overlay::ZiniOverlayMessage::SetAlgorithm(..) => {
+                            self.zini_overlay.update(msg);
+                            if self.zini_overlay.is_active() {
+                                self.zini_overlay.start(self.game.board());
+                            }
+                        },
+                        _ => self.zini_overlay.update(msg),
+                    }
+                },
+                PlayerMessage::Replay(msg) => {
+                    trace!("Replay message received: {:?}", msg);
+                    match msg {
+                        replay::ReplayMessage::Open => {
+                            self.zini_overlay.stop();
+                            self.replay = replay::ReplayViewer::new(
+                                self.game.recording().clone(),
+                                self.game.board_area(),
+                                self.config.cell_size,
+                                Arc::clone(&self.skin),
+                                self.viewport,
+                            );
+                        },
+                        replay::ReplayMessage::Close => {
+                            debug!("Closing replay");
+                            self.replay = None;
+                        },
+                        _ => {
+                            if let Some(replay) = &mut self.replay {
+                                replay.update(msg);
+                            }
+                        },
+                    }
+                },
+                PlayerMessage::Save(msg) => {
+                    trace!("Save message received: {:?}", msg);
+                    match msg {
+                        SaveMessage::Load => {
+                            let Some(name) = self.saves.selected().map(str::to_string) else {
+                                break 'out;
+                            };
+                            let restored =
+                                save::SavedGame::load(&name).is_ok_and(|saved| self.restore_game(&saved, &mut tasks));
+                            if restored {
+                                info!("Saved game {:?} loaded", name);
+                            } else {
+                                error!("Failed to load saved game {:?}", name);
+                                tasks.push(Task::done(PlayerMessage::Request(RequestMessage::ShowErrorModal(
+                                    format!("Failed to load {name}, check the log for details"),
+                                ))));
+                            }
+                        },
+                        SaveMessage::RestoreAutosave => {
+                            let restored = match save::SavedGame::load_autosave() {
+                                Ok(Some(saved)) => self.restore_game(&saved, &mut tasks),
+                                _ => false,
+                            };
+                            if restored {
+                                info!("Restored the game from the last session");
+                            } else if self.config.no_guess {
+                                debug!("No-guess mode enabled, generating the first board");
+                                self.generate_no_guess(&mut tasks);
+                            }
+                        },
+                        msg => {
+                            if let Err(err) = self.saves.update(msg, &self.game) {
+                                tasks.push(Task::done(PlayerMessage::Request(RequestMessage::ShowErrorModal(err))));
+                            }
+                        },
+                    }
+                },
+                PlayerMessage::Preset(msg) => {
+                    trace!("Preset message received: {:?}", msg);
+                    if !self.presets.update(msg, &mut self.config, &mut self.config_update) {
+                        break 'out;
+                    }
+                    if self.config.no_guess {
+                        self.text_input_states[..3]
+                            .iter_mut()
+                            .zip(self.config.board)
+                            .for_each(|(state, value)| *state = value.to_string());
+                        self.generate_no_guess(&mut tasks);
+                        break 'out;
+                    }
+                    self.new_game(
+                        Box::new(board::StandardBoard::new(
+                            self.config.board[0],
+                            self.config.board[1],
+                            self.config.board[2],
+                            self.config.chord_mode,
+                            self.config.first_click_policy,
+                        )),
+                        &mut tasks,
+                    );
+                },
                 PlayerMessage::Import(msg) => {
                     trace!("Import message received: {:?}", msg);
                     match msg {
@@ -404,19 +1029,11 @@ impl Player {
                         ImportMessage::StartImport(encode_type, string) => {
                             debug!("Start import board with type {} and text {}", encode_type, string);
                             let board_to_import = Arc::clone(&self.board_to_import);
-                            let decoder = match encode_type {
-                                encode_decode::EncodeType::Ascii | encode_decode::EncodeType::AsciiWithNumbers => {
-                                    encode_decode::ascii::decode
-                                },
-                                encode_decode::EncodeType::Base64 => encode_decode::base64::decode,
-                                encode_decode::EncodeType::PttUrl => encode_decode::ptt_url::decode,
-                                encode_decode::EncodeType::LlamaUrl => encode_decode::llama_url::decode,
-                            };
                             let import = self.boxed_import(board::StandardBoard::import);
                             self.import_button_state = ImportButtonState::Importing;
                             tasks.push(Task::perform(
                                 async move {
-                                    let pack = decoder(&string);
+                                    let pack = encode_type.decode(&string);
                                     let board = pack.and_then(import);
                                     let mut lock = board_to_import.lock().await;
                                     *lock = board;
@@ -476,30 +1093,28 @@ impl Player {
                         ExportMessage::StartExport(encode_type) => {
                             debug!("Start export board with type {}", encode_type);
                             self.export_button_state = ExportButtonState::Exporting;
-                            let cell_contents = self.game.board().cell_contents().clone();
-                            let start_pos = self.game.board().start_position();
-                            let encoder = match encode_type {
-                                encode_decode::EncodeType::Ascii => encode_decode::ascii::encode,
-                                encode_decode::EncodeType::AsciiWithNumbers => {
-                                    encode_decode::ascii::encode_with_numbers
-                                },
-                                encode_decode::EncodeType::Base64 => encode_decode::base64::encode,
-                                encode_decode::EncodeType::PttUrl => {
-                                    |cell_content: &_, _| encode_decode::ptt_url::encode(cell_content)
-                                },
-                                encode_decode::EncodeType::LlamaUrl => {
-                                    |cell_content: &_, _| encode_decode::llama_url::encode(cell_content)
-                                },
+                            let board = self.game.board();
+                            let pack = board::ImportPack {
+                                cell_contents: board.cell_contents().clone(),
+                                mines: board.mines(),
+                                start_position: board.start_position(),
+                                cell_states: Some(board.cell_states().clone()),
+                                seed: board.board_seed(),
                             };
                             tasks.push(Task::perform(
-                                async move {
-                                    let encoded = encoder(&cell_contents, start_pos);
-                                    ExportMessage::ExportCompleted(encoded)
-                                },
+                                async move { ExportMessage::ExportCompleted(encode_type.encode(&pack)) },
                                 PlayerMessage::Export,
                             ));
                         },
-                        ExportMessage::ExportCompleted(data) => {
+                        ExportMessage::ExportCompleted(None) => {
+                            error!("Failed to export board: no seed to export");
+                            self.export_button_state = ExportButtonState::Export;
+                            tasks.push(Task::done(PlayerMessage::Request(RequestMessage::ShowErrorModal(
+                                "Only boards generated from a seed can be exported as one, after the first click"
+                                    .to_string(),
+                            ))));
+                        },
+                        ExportMessage::ExportCompleted(Some(data)) => {
                             info!("Board exported successfully to clipboard");
                             trace!("Encoded data: {}", data);
                             tasks.push(iced::clipboard::write(data));
@@ -528,7 +1143,9 @@ impl Player {
             let update = std::mem::take(&mut self.config_update);
             tasks.insert(
                 0,
-                Task::done(PlayerMessage::Request(RequestMessage::SyncConfigToApp(update))),
+                Task::done(PlayerMessage::Request(RequestMessage::SyncConfigToApp(Box::new(
+                    update,
+                )))),
             );
         }
 
@@ -536,8 +1153,7 @@ impl Player {
     }
 
     pub fn view_sidebar(&self, width: f32) -> iced::Element<'_, PlayerMessage> {
-        let enable_button = !matches!(self.export_button_state, ExportButtonState::Exporting)
-            && !matches!(self.import_button_state, ImportButtonState::Importing);
+        let enable_button = !self.is_busy();
 
         let export_button_label = match &self.export_button_state {
             ExportButtonState::Copied { .. } => "Copied!",
@@ -551,6 +1167,7 @@ impl Player {
         let board_control = iced::widget::container(
             iced::widget::column![
                 iced::widget::center_x(iced::widget::text("Board Config").size(18)),
+                self.presets.view_builtin(enable_button).map(PlayerMessage::Preset),
                 iced::widget::row![
                     iced::widget::text("Width:").size(16).width(iced::FillPortion(1)),
                     iced::widget::TextInput::new("width", &self.text_input_states[TextInputType::Width as usize])
@@ -572,11 +1189,40 @@ impl Player {
                         .on_input(PlayerMessage::TextInputChanged.with(TextInputType::Mines))
                 ]
                 .align_y(iced::alignment::Vertical::Center),
+                self.presets
+                    .view_custom(&self.config, enable_button)
+                    .map(PlayerMessage::Preset),
+                iced::widget::row![
+                    iced::widget::text("Seed:").size(14).width(iced::FillPortion(1)),
+                    iced::widget::text(
+                        self.game
+                            .board()
+                            .seed()
+                            .map_or_else(|| "-".to_string(), |seed| format!("{:016x}", seed))
+                    )
+                    .size(14)
+                    .width(iced::FillPortion(2))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
                 iced::widget::center_x(
-                    iced::widget::button(iced::widget::text("New Game").align_x(iced::alignment::Horizontal::Center))
+                    iced::widget::button(
+                        iced::widget::text(if self.generation.is_some() {
+                            "Generating..."
+                        } else {
+                            "New Game"
+                        })
+                        .align_x(iced::alignment::Horizontal::Center)
+                    )
+                    .width(width * 0.6)
+                    .on_press_maybe(enable_button.then_some(PlayerMessage::Game(GameMessage::FaceClicked)))
+                ),
+                iced::widget::center_x(
+                    iced::widget::button(iced::widget::text("Daily").align_x(iced::alignment::Horizontal::Center))
                         .width(width * 0.6)
-                        .on_press_maybe(enable_button.then_some(PlayerMessage::Game(GameMessage::FaceClicked)))
+                        .on_press_maybe(enable_button.then_some(PlayerMessage::Daily))
                 ),
+                self.daily
+                    .map(|date| iced::widget::center_x(self.view_daily_result(date))),
                 iced::widget::center_x(
                     iced::widget::button(iced::widget::text("Continue").align_x(iced::alignment::Horizontal::Center))
                         .width(width * 0.6)
@@ -585,6 +1231,24 @@ impl Player {
                                 .then_some(PlayerMessage::Game(GameMessage::Continue))
                         )
                 ),
+                iced::widget::center_x(
+                    iced::widget::row![
+                        iced::widget::button(iced::widget::text("Undo").align_x(iced::alignment::Horizontal::Center))
+                            .width(iced::FillPortion(1))
+                            .on_press_maybe(
+                                (enable_button && self.game.board().can_undo())
+                                    .then_some(PlayerMessage::Game(GameMessage::Undo))
+                            ),
+                        iced::widget::button(iced::widget::text("Redo").align_x(iced::alignment::Horizontal::Center))
+                            .width(iced::FillPortion(1))
+                            .on_press_maybe(
+                                (enable_button && self.game.board().can_redo())
+                                    .then_some(PlayerMessage::Game(GameMessage::Redo))
+                            ),
+                    ]
+                    .spacing(4)
+                    .width(width * 0.6)
+                ),
                 iced::widget::center_x(
                     iced::widget::button(iced::widget::text("Replay").align_x(iced::alignment::Horizontal::Center))
                         .width(width * 0.6)
@@ -593,6 +1257,19 @@ impl Player {
                                 .then_some(PlayerMessage::Game(GameMessage::Replay))
                         )
                 ),
+                iced::widget::center_x(
+                    iced::widget::button(
+                        iced::widget::text("Watch Replay").align_x(iced::alignment::Horizontal::Center)
+                    )
+                    .width(width * 0.6)
+                    .on_press_maybe(
+                        (enable_button
+                            && self.replay.is_none()
+                            && self.game.board().state().is_end()
+                            && self.game.recording().layout().is_some())
+                        .then_some(PlayerMessage::Replay(replay::ReplayMessage::Open))
+                    )
+                ),
                 iced::widget::center_x(
                     iced::widget::button(
                         iced::widget::text(export_button_label).align_x(iced::alignment::Horizontal::Center)
@@ -636,10 +1313,31 @@ impl Player {
         iced::widget::container(
             iced::widget::column![
                 iced::widget::center_x(iced::widget::text("Control Panel").size(20)),
+                self.replay
+                    .as_ref()
+                    .map(|replay| iced::widget::center_x(Self::view_replay_control(replay, width))),
                 iced::widget::center_x(board_control),
+                iced::widget::center_x(
+                    self.saves
+                        .view(&self.game, enable_button, width)
+                        .map(PlayerMessage::Save)
+                ),
                 iced::widget::checkbox(self.config.chord_mode == board::ChordMode::LeftClick)
                     .label("Left-click chord")
                     .on_toggle(PlayerMessage::ChordModeToggled),
+                iced::widget::row![
+                    iced::widget::text("First Click:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(
+                        &board::FirstClickPolicy::ALL[..],
+                        Some(self.config.first_click_policy),
+                        PlayerMessage::FirstClickPolicySelected
+                    )
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::checkbox(self.config.no_guess)
+                    .label("No Guess")
+                    .on_toggle(PlayerMessage::NoGuessToggled),
                 iced::widget::checkbox(self.show_probabilities)
                     .label("Show Probability")
                     .on_toggle(|enabled| PlayerMessage::Solver(overlay::SolverOverlayMessage::SetEnabled(enabled))),
@@ -648,7 +1346,61 @@ impl Player {
                     .on_toggle_maybe(self.show_probabilities.then_some(|admit_flags| {
                         PlayerMessage::Solver(overlay::SolverOverlayMessage::SetAdmitFlags(admit_flags))
                     })),
-                cell_size
+                iced::widget::row![
+                    iced::widget::text("Display:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(
+                        &overlay::ProbabilityDisplay::ALL[..],
+                        Some(self.solver_display),
+                        |display| PlayerMessage::Solver(overlay::SolverOverlayMessage::SetDisplay(display))
+                    )
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                cell_size,
+                iced::widget::row![
+                    iced::widget::text("Skin:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::button(iced::widget::text(self.skin.name.as_str()))
+                        .width(iced::FillPortion(1))
+                        .on_press(PlayerMessage::Request(RequestMessage::ShowSkinModal))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::row![
+                    iced::widget::text("Solution:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(
+                        &overlay::ZiniAlgorithm::ALL[..],
+                        Some(self.zini_overlay.algorithm()),
+                        |algorithm| PlayerMessage::Zini(overlay::ZiniOverlayMessage::SetAlgorithm(algorithm))
+                    )
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::center_x(
+                    iced::widget::button(
+                        iced::widget::text(match self.zini_overlay.progress() {
+                            Some((step, total)) => format!("Hide Solution ({}/{})", step, total),
+                            None => "Show Solution".to_string(),
+                        })
+                        .align_x(iced::alignment::Horizontal::Center)
+                    )
+                    .width(width * 0.6)
+                    .on_press_maybe(if self.zini_overlay.is_active() {
+                        Some(PlayerMessage::Zini(overlay::ZiniOverlayMessage::Stop))
+                    } else {
+                        self.game
+                            .board()
+                            .state()
+                            .is_end()
+                            .then_some(PlayerMessage::Zini(overlay::ZiniOverlayMessage::Start))
+                    })
+                ),
+                iced::widget::center_x(
+                    iced::widget::button(
+                        iced::widget::text("Key Bindings").align_x(iced::alignment::Horizontal::Center)
+                    )
+                    .width(width * 0.6)
+                    .on_press(PlayerMessage::Request(RequestMessage::ShowSettingsModal))
+                ),
+                iced::widget::center_x(self.view_statistics()),
             ]
             .spacing(4)
             .padding(4),
@@ -657,10 +1409,152 @@ impl Player {
         .into()
     }
 
+    fn view_replay_control(replay: &replay::ReplayViewer, width: f32) -> iced::Element<'_, PlayerMessage> {
+        let duration = replay.duration().as_secs_f64();
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Replay").size(18)),
+                iced::widget::row![
+                    iced::widget::button(
+                        iced::widget::text(if replay.is_playing() { "Pause" } else { "Play" })
+                            .align_x(iced::alignment::Horizontal::Center)
+                    )
+                    .width(iced::FillPortion(1))
+                    .on_press(PlayerMessage::Replay(replay::ReplayMessage::TogglePlaying)),
+                    iced::widget::button(iced::widget::text("Close").align_x(iced::alignment::Horizontal::Center))
+                        .width(iced::FillPortion(1))
+                        .on_press(PlayerMessage::Replay(replay::ReplayMessage::Close)),
+                ]
+                .spacing(4),
+                iced::widget::row![
+                    iced::widget::text("Speed:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(&replay::ReplaySpeed::ALL[..], Some(replay.speed()), |speed| {
+                        PlayerMessage::Replay(replay::ReplayMessage::SetSpeed(speed))
+                    })
+                    .width(iced::FillPortion(1))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::slider(0.0..=duration, replay.position().as_secs_f64(), |seconds| {
+                    PlayerMessage::Replay(replay::ReplayMessage::Seek(seconds))
+                })
+                .step(0.01),
+                iced::widget::center_x(
+                    iced::widget::text(format!("{:.2} / {:.2} s", replay.position().as_secs_f64(), duration)).size(14)
+                ),
+            ]
+            .spacing(4)
+            .padding(6)
+            .width(width * 0.9),
+        )
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn view_daily_result(&self, date: jiff::civil::Date) -> iced::Element<'_, PlayerMessage> {
+        let result = self.daily_history.result(date).cloned().unwrap_or_default();
+        let best = result
+            .best
+            .map_or_else(|| "-".to_string(), |best| format!("{:.2} s", best.time.as_secs_f64()));
+        iced::widget::column![
+            iced::widget::text(format!("Daily {}", date)).size(14),
+            iced::widget::text(format!("Won {}/{}, best {}", result.wins, result.attempts, best)).size(14),
+            result.best.map(|best| iced::widget::text(format!(
+                "3BV/s {:.3}, IOE {:.3}",
+                best.bbbv_per_second(),
+                best.ioe()
+            ))
+            .size(14)),
+        ]
+        .align_x(iced::alignment::Horizontal::Center)
+        .into()
+    }
+
+    fn view_statistics(&self) -> iced::Element<'_, PlayerMessage> {
+        let statistics = self.game.statistics();
+        let ratio = |value: f64| {
+            if value.is_finite() {
+                format!("{:.3}", value)
+            } else {
+                "-".to_string()
+            }
+        };
+        let (bbbv, openings, islands) = statistics.layout().map_or_else(
+            || ("-".to_string(), "-".to_string(), "-".to_string()),
+            |layout| {
+                (
+                    layout.bbbv().to_string(),
+                    layout.openings().to_string(),
+                    layout.islands().to_string(),
+                )
+            },
+        );
+        // The ZiNi is computed in the background once the game ends
+        let zini = |zini: Option<usize>| match zini {
+            Some(zini) => zini.to_string(),
+            None if statistics.pending_zini(self.game.board()).is_some() => "…".to_string(),
+            None => "-".to_string(),
+        };
+        let stat_row = |label: &'static str, value: String| {
+            iced::widget::row![
+                iced::widget::text(label).size(14).width(iced::FillPortion(1)),
+                iced::widget::text(value).size(14).width(iced::FillPortion(1)),
+            ]
+        };
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Statistics").size(18)),
+                self.game
+                    .is_assisted()
+                    .then(|| iced::widget::center_x(iced::widget::text("Assisted (undo used)").size(14))),
+                stat_row("3BV:", format!("{}/{}", statistics.solved_bbbv(), bbbv)),
+                stat_row("Openings:", openings),
+                stat_row("Islands:", islands),
+                stat_row(
+                    "Clicks:",
+                    format!(
+                        "{}+{}+{}",
+                        statistics.left_clicks(),
+                        statistics.right_clicks(),
+                        statistics.chord_clicks()
+                    )
+                ),
+                stat_row("Wasted:", statistics.wasted_clicks().to_string()),
+                stat_row("ZiNi:", zini(statistics.zini())),
+                stat_row("HZiNi:", zini(statistics.human_zini())),
+                stat_row("IOE:", ratio(statistics.ioe())),
+                stat_row("Throughput:", ratio(statistics.throughput())),
+                stat_row("3BV/s:", ratio(statistics.bbbv_per_second(self.game.timer().elapsed()))),
+            ]
+            .spacing(2)
+            .padding(6),
+        )
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+
     pub fn view_game(&self) -> iced::Element<'_, PlayerMessage> {
-        iced::widget::Stack::with_capacity(2)
+        if let Some(replay) = &self.replay {
+            return replay.view().map(PlayerMessage::Replay);
+        }
+        iced::widget::Stack::with_capacity(3)
             .push(self.game.view().map(PlayerMessage::Game))
             .push(self.solver_overlay.view().map(PlayerMessage::Solver))
+            .push(self.zini_overlay.view().map(PlayerMessage::Zini))
             .into()
     }
 
@@ -699,6 +1593,52 @@ impl Player {
             _ => iced::Subscription::none(),
         };
 
-        iced::Subscription::batch([listen, import_timer, export_timer])
+        let game_timer = if self.game.timer().is_running() {
+            iced::time::every(std::time::Duration::from_millis(100))
+                .map(|_| PlayerMessage::Game(GameMessage::TimerTick))
+        } else {
+            iced::Subscription::none()
+        };
+
+        let zoom_timer = if self.zoom_settles_at.is_some() {
+            iced::time::every(std::time::Duration::from_millis(50)).map(|_| PlayerMessage::ZoomTick)
+        } else {
+            iced::Subscription::none()
+        };
+
+        let zini_timer = if self.zini_overlay.is_playing() {
+            iced::time::every(std::time::Duration::from_millis(200))
+                .map(|_| PlayerMessage::Zini(overlay::ZiniOverlayMessage::Tick))
+        } else {
+            iced::Subscription::none()
+        };
+
+        let replay_timer = if self.replay.as_ref().is_some_and(|replay| replay.is_playing()) {
+            iced::time::every(std::time::Duration::from_millis(20))
+                .map(|now| PlayerMessage::Replay(replay::ReplayMessage::Tick(now)))
+        } else {
+            iced::Subscription::none()
+        };
+
+        iced::Subscription::batch([
+            listen,
+            import_timer,
+            export_timer,
+            game_timer,
+            zoom_timer,
+            zini_timer,
+            replay_timer,
+        ])
+    }
+
+    /// Keys for the key bindings and the keyboard cursor.
+    ///
+    /// Only keys that no widget has captured are used, so that typing in a text input does not
+    /// play the game.
+    pub fn keyboard_subscription() -> iced::Subscription<PlayerMessage> {
+        iced::keyboard::listen().filter_map(|event| match event {
+            iced::keyboard::Event::KeyPressed { key, modifiers, .. } => Some(PlayerMessage::KeyPressed(key, modifiers)),
+            _ => None,
+        })
     }
 }
@@ -1,11 +1,16 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use super::recording::{self, Recording};
 use crate::{
-    base::board,
+    base::{
+        board,
+        statistics::{Click, Statistics},
+        timer::Timer,
+    },
     ui::{board_area::BoardArea, skin},
 };
 use iced::widget::canvas;
-use log::{debug, trace};
+use log::{debug, info, trace};
 
 #[derive(Debug, Clone, Copy)]
 pub enum BoardMessage {
@@ -14,20 +19,64 @@ pub enum BoardMessage {
     Chord { x: usize, y: usize, is_left: bool },
 }
 
+/// Moves and clicks of the keyboard cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Open,
+    Flag,
+    Chord,
+    Hide,
+}
+
+impl CursorAction {
+    /// Arrows or hjkl move the cursor, Enter or D opens, F flags, S chords and Escape hides it.
+    pub fn from_key(key: iced::keyboard::Key<&str>) -> Option<Self> {
+        use iced::keyboard::{Key, key::Named};
+        match key {
+            Key::Named(Named::ArrowUp) | Key::Character("k") => Some(CursorAction::Up),
+            Key::Named(Named::ArrowDown) | Key::Character("j") => Some(CursorAction::Down),
+            Key::Named(Named::ArrowLeft) | Key::Character("h") => Some(CursorAction::Left),
+            Key::Named(Named::ArrowRight) | Key::Character("l") => Some(CursorAction::Right),
+            Key::Named(Named::Enter) | Key::Character("d") => Some(CursorAction::Open),
+            Key::Character("f") => Some(CursorAction::Flag),
+            Key::Character("s") => Some(CursorAction::Chord),
+            Key::Named(Named::Escape) => Some(CursorAction::Hide),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GameMessage {
     Board(BoardMessage),
+    Cursor(CursorAction),
     FaceClicked,
-    PressedPositionChanged,
+    PressedPositionChanged(MouseState),
     Resize {
         cell_size: u32,
         board_area: BoardArea,
         skin: Arc<skin::Skin>,
     },
     ChordModeChanged(board::ChordMode),
+    FirstClickPolicyChanged(board::FirstClickPolicy),
     ViewportChanged(iced::Rectangle),
+    /// Ctrl+wheel over the board, by `lines` of the wheel, keeping `anchor` of the canvas under
+    /// the cursor. Handled by the player, which owns the cell size.
+    Zoom {
+        lines: f32,
+        anchor: Option<iced::Point>,
+    },
+    /// Middle-button drag, by how much to scroll the board. Handled by the player.
+    Pan(iced::Vector),
     Continue,
     Replay,
+    Undo,
+    Redo,
+    TimerTick,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -39,6 +88,18 @@ pub enum MouseState {
     BothDown(Option<(usize, usize)>),
 }
 
+/// State of the game canvas.
+#[derive(Debug, Default)]
+pub struct CanvasState {
+    mouse: MouseState,
+    modifiers: iced::keyboard::Modifiers,
+    /// Where the cursor is in the window. Unlike the cursor given to the canvas, it does not
+    /// move with the scrollable.
+    window_cursor: iced::Point,
+    /// Dragging with the middle button.
+    panning: bool,
+}
+
 impl From<BoardMessage> for GameMessage {
     fn from(message: BoardMessage) -> Self {
         GameMessage::Board(message)
@@ -52,10 +113,38 @@ pub struct Game {
     cache: canvas::Cache,
     skin: Arc<skin::Skin>,
     viewport: iced::Rectangle,
+    timer: Timer,
+    /// Seconds shown by the timer when the canvas was last invalidated.
+    timer_seconds: u64,
+    statistics: Statistics,
+    recording: Recording,
+    /// Whether undo has been used in this game.
+    assisted: bool,
+    /// Whether the game is driven by a replay instead of the mouse: canvas input is ignored, the
+    /// pressed cells follow `mouse` and the timer follows the replay clock.
+    playback: bool,
+    mouse: MouseState,
+    /// The cell under the keyboard cursor, `None` until a key is used.
+    cursor: Option<(usize, usize)>,
 }
 
 impl Game {
+    /// Largest value the timer can show with three digits.
+    const TIMER_MAX_SECONDS: u64 = 999;
+
     pub fn new(board: Box<dyn board::Board>, board_area: BoardArea, cell_size: u32, skin: Arc<skin::Skin>) -> Self {
+        // A position played before the game was created cannot be played back from a fresh board
+        let recording = if board
+            .cell_states()
+            .iter()
+            .all(|&state| state == board::CellState::Closed)
+        {
+            Recording::new(board.chord_mode())
+        } else {
+            Recording::resumed(board.chord_mode())
+        };
+        let mut statistics = Statistics::default();
+        statistics.refresh(board.as_ref());
         Self {
             board,
             board_area,
@@ -63,6 +152,27 @@ impl Game {
             cache: canvas::Cache::new(),
             skin,
             viewport: Default::default(),
+            timer: Timer::default(),
+            timer_seconds: 0,
+            statistics,
+            recording,
+            assisted: false,
+            playback: false,
+            mouse: MouseState::Idle,
+            cursor: None,
+        }
+    }
+
+    /// Create a game to be driven by a replay, see [`Game::set_playback_time`].
+    pub fn new_playback(
+        board: Box<dyn board::Board>,
+        board_area: BoardArea,
+        cell_size: u32,
+        skin: Arc<skin::Skin>,
+    ) -> Self {
+        Self {
+            playback: true,
+            ..Self::new(board, board_area, cell_size, skin)
         }
     }
 
@@ -74,6 +184,82 @@ impl Game {
         self.cell_size
     }
 
+    pub fn board_area(&self) -> BoardArea {
+        self.board_area
+    }
+
+    pub fn timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Set the ZiNi of the layout once it has been computed, see [`Statistics::pending_zini`].
+    pub fn set_zini(&mut self, greedy: usize, human: usize) {
+        self.statistics.set_zini(greedy, human);
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn is_assisted(&self) -> bool {
+        self.assisted
+    }
+
+    /// Continue a saved game whose board has just been restored, with `elapsed` on the timer.
+    ///
+    /// The timer resumes with the next click.
+    pub fn restore(&mut self, elapsed: Duration, assisted: bool) {
+        self.timer.set(elapsed);
+        self.assisted = assisted;
+        self.update(GameMessage::TimerTick);
+        self.cache.clear();
+    }
+
+    /// Show `elapsed` on the timer of a playback game.
+    pub fn set_playback_time(&mut self, elapsed: Duration) {
+        self.timer.set(elapsed);
+        self.update(GameMessage::TimerTick);
+    }
+
+    /// Start the timer when the game starts and stop it when the game ends.
+    fn sync_timer(&mut self) {
+        if self.playback {
+            return;
+        }
+        match self.board.state() {
+            board::BoardState::NotStarted => self.timer.reset(),
+            board::BoardState::InProgress { .. } => self.timer.start(),
+            board::BoardState::Won | board::BoardState::Lost { .. } => {
+                if self.timer.is_running() {
+                    self.timer.stop();
+                    debug!("Game ended after {:?}", self.timer.elapsed());
+                    self.log_statistics();
+                }
+            },
+        }
+    }
+
+    fn log_statistics(&self) {
+        let statistics = &self.statistics;
+        let Some(layout) = statistics.layout() else {
+            return;
+        };
+        info!(
+            "3BV {}/{}, clicks {} ({} wasted), IOE {:.3}, throughput {:.3}, 3BV/s {:.3}",
+            statistics.solved_bbbv(),
+            layout.bbbv(),
+            statistics.clicks(),
+            statistics.wasted_clicks(),
+            statistics.ioe(),
+            statistics.throughput(),
+            statistics.bbbv_per_second(self.timer.elapsed()),
+        );
+    }
+
     fn cell_at(&self, pos: iced::Point) -> Option<(usize, usize)> {
         let x = ((pos.x - self.board_area.game_area.x) / self.cell_size as f32).floor() as isize;
         let y = ((pos.y - self.board_area.game_area.y) / self.cell_size as f32).floor() as isize;
@@ -108,38 +294,104 @@ impl Game {
                     debug!("Board is in end state ({:?}), ignoring input", self.board.state());
                     return false;
                 }
-                match board_msg {
+                // Every effective click opens a cell, toggles a flag or starts the game, all of
+                // which show in the board state
+                let state_before = self.board.state();
+                let click = match board_msg {
                     BoardMessage::Left { x, y } => {
                         trace!("Left click at ({}, {})", x, y);
                         self.board.left_click(x, y);
+                        Click::Left
                     },
                     BoardMessage::Right { x, y } => {
                         trace!("Right click at ({}, {})", x, y);
                         self.board.right_click(x, y);
+                        Click::Right
                     },
                     BoardMessage::Chord { x, y, is_left } => {
                         trace!("Chord click at ({}, {}), is_left: {}", x, y, is_left);
                         self.board.chord_click(x, y, is_left);
+                        Click::Chord
                     },
-                }
+                };
+                let wasted = self.board.state() == state_before;
+                self.statistics.record(click, wasted, self.board.as_ref());
+                self.recording.record(recording::Action::Board(board_msg));
+                self.recording.capture_layout(self.board.as_ref());
+                // The buttons have been released
+                self.mouse = MouseState::Idle;
+                self.sync_timer();
                 self.cache.clear();
                 return true;
             },
+            GameMessage::Cursor(action) => {
+                trace!("Cursor action: {:?}", action);
+                let Some((x, y)) = self.cursor else {
+                    if action != CursorAction::Hide {
+                        // The first key only shows the cursor, in the middle of the board
+                        self.cursor = Some((self.board.width() / 2, self.board.height() / 2));
+                        self.cache.clear();
+                    }
+                    return false;
+                };
+                let board_msg = match action {
+                    CursorAction::Up => {
+                        self.cursor = Some((x, y.saturating_sub(1)));
+                        None
+                    },
+                    CursorAction::Down => {
+                        self.cursor = Some((x, (y + 1).min(self.board.height() - 1)));
+                        None
+                    },
+                    CursorAction::Left => {
+                        self.cursor = Some((x.saturating_sub(1), y));
+                        None
+                    },
+                    CursorAction::Right => {
+                        self.cursor = Some(((x + 1).min(self.board.width() - 1), y));
+                        None
+                    },
+                    CursorAction::Open => Some(BoardMessage::Left { x, y }),
+                    CursorAction::Flag => Some(BoardMessage::Right { x, y }),
+                    CursorAction::Chord => Some(BoardMessage::Chord { x, y, is_left: true }),
+                    CursorAction::Hide => {
+                        self.cursor = None;
+                        None
+                    },
+                };
+                self.cache.clear();
+                if let Some(board_msg) = board_msg {
+                    return self.update(GameMessage::Board(board_msg));
+                }
+            },
             GameMessage::FaceClicked => {
                 debug!("Face clicked, resetting the board");
                 self.board.reset();
+                self.timer.reset();
+                self.statistics = Statistics::default();
+                self.recording = Recording::new(self.board.chord_mode());
+                self.assisted = false;
                 self.cache.clear();
                 return true;
             },
-            GameMessage::PressedPositionChanged => {
+            GameMessage::PressedPositionChanged(mouse) => {
                 trace!("PressedPositionChanged");
+                self.mouse = mouse;
+                if !self.board.state().is_end() {
+                    self.recording.record(recording::Action::Mouse(mouse));
+                }
                 self.cache.clear();
             },
             GameMessage::ChordModeChanged(mode) => {
                 debug!("Changing chord mode to {:?}", mode);
                 self.board.set_chord_mode(mode);
+                self.recording.record(recording::Action::ChordMode(mode));
                 self.cache.clear();
             },
+            GameMessage::FirstClickPolicyChanged(policy) => {
+                debug!("Changing first click policy to {:?}", policy);
+                self.board.set_first_click_policy(policy);
+            },
             GameMessage::ViewportChanged(viewport) => {
                 trace!("Viewport changed to {:?}", viewport);
                 self.viewport = viewport;
@@ -156,17 +408,57 @@ impl Game {
                 self.board_area = board_area;
                 self.cache.clear();
             },
+            // The player zooms and scrolls the scrollable around the canvas
+            GameMessage::Zoom { .. } | GameMessage::Pan(..) => {},
             GameMessage::Continue => {
                 debug!("Continuing from lost state, resetting the board");
                 self.board.resume();
+                self.recording.record(recording::Action::Continue);
+                self.statistics.refresh(self.board.as_ref());
+                self.sync_timer();
                 self.cache.clear();
             },
             GameMessage::Replay => {
                 debug!("Replaying the current game, resetting the board");
                 self.board.replay();
+                // The replayed board is already in progress, so the timer waits for the first click
+                self.timer.reset();
+                self.statistics.clear_clicks();
+                self.statistics.refresh(self.board.as_ref());
+                self.recording = Recording::new(self.board.chord_mode());
+                self.recording.capture_layout(self.board.as_ref());
+                self.assisted = false;
+                self.cache.clear();
+                return true;
+            },
+            GameMessage::Undo | GameMessage::Redo => {
+                let undo = matches!(message, GameMessage::Undo);
+                let done = if undo { self.board.undo() } else { self.board.redo() };
+                if !done {
+                    debug!("Nothing to {}", if undo { "undo" } else { "redo" });
+                    return false;
+                }
+                if undo && !self.assisted {
+                    info!("Undo used, the game is marked as assisted");
+                    self.assisted = true;
+                }
+                self.recording.record(if undo {
+                    recording::Action::Undo
+                } else {
+                    recording::Action::Redo
+                });
+                self.statistics.refresh(self.board.as_ref());
+                self.sync_timer();
                 self.cache.clear();
                 return true;
             },
+            GameMessage::TimerTick => {
+                let seconds = self.timer.elapsed().as_secs().min(Self::TIMER_MAX_SECONDS);
+                if seconds != self.timer_seconds {
+                    self.timer_seconds = seconds;
+                    self.cache.clear();
+                }
+            },
         }
         false
     }
@@ -180,7 +472,7 @@ impl Game {
 }
 
 impl canvas::Program<GameMessage> for Game {
-    type State = MouseState;
+    type State = CanvasState;
 
     fn update(
         &self,
@@ -189,7 +481,56 @@ impl canvas::Program<GameMessage> for Game {
         bounds: iced::Rectangle,
         cursor: iced::mouse::Cursor,
     ) -> Option<canvas::Action<GameMessage>> {
+        if self.playback {
+            return None;
+        }
+        let CanvasState {
+            mouse: state,
+            modifiers,
+            window_cursor,
+            panning,
+        } = state;
+        let last_window_cursor = *window_cursor;
+        if let iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) = event {
+            *window_cursor = *position;
+        }
         match event {
+            iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(new_modifiers)) => {
+                *modifiers = *new_modifiers;
+                None
+            },
+            iced::Event::Mouse(iced::mouse::Event::WheelScrolled { delta })
+                if modifiers.command() && cursor.is_over(bounds) =>
+            {
+                let lines = match *delta {
+                    iced::mouse::ScrollDelta::Lines { y, .. } => y,
+                    // The scrollable takes a line as 60 pixels
+                    iced::mouse::ScrollDelta::Pixels { y, .. } => y / 60.0,
+                };
+                trace!("Ctrl+wheel by {} line(s), publishing Zoom", lines);
+                Some(
+                    canvas::Action::publish(GameMessage::Zoom {
+                        lines,
+                        anchor: cursor.position_in(bounds),
+                    })
+                    .and_capture(),
+                )
+            },
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Middle))
+                if cursor.is_over(bounds) =>
+            {
+                trace!("Middle button pressed, start panning");
+                *panning = true;
+                Some(canvas::Action::capture())
+            },
+            iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Middle)) if *panning => {
+                trace!("Middle button released, stop panning");
+                *panning = false;
+                Some(canvas::Action::capture())
+            },
+            iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) if *panning => {
+                Some(canvas::Action::publish(GameMessage::Pan(last_window_cursor - *position)).and_capture())
+            },
             iced::Event::Mouse(mouse_event) => {
                 let cursor_position = cursor.position_in(bounds);
                 let position = cursor_position.and_then(|pos| {
@@ -211,25 +552,25 @@ impl canvas::Program<GameMessage> for Game {
                                 *state = MouseState::LeftDown(position);
                                 trace!("State changed from Idle to LeftDown");
                                 trace!("Publishing PressedPositionChanged");
-                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                             },
                             MouseState::LeftDown { .. } => {
                                 *state = MouseState::LeftDown(position);
                                 trace!("State changed from LeftDown to LeftDown, maybe lost focus?");
                                 trace!("Publishing PressedPositionChanged");
-                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                             },
                             MouseState::RightDown => {
                                 *state = MouseState::BothDown(position);
                                 trace!("State changed from RightDown to BothDown");
                                 trace!("Publishing PressedPositionChanged");
-                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                             },
                             MouseState::BothDown { .. } => {
                                 *state = MouseState::LeftDown(position);
                                 trace!("State changed from BothDown to LeftDown, maybe lost focus?");
                                 trace!("Publishing PressedPositionChanged");
-                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                             },
                         }
                     },
@@ -260,7 +601,7 @@ impl canvas::Program<GameMessage> for Game {
                                 *state = MouseState::BothDown(position);
                                 trace!("State changed from LeftDown to BothDown");
                                 trace!("Publishing PressedPositionChanged");
-                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                                Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                             },
                             MouseState::RightDown => {
                                 trace!("State changed from RightDown to RightDown, maybe lost focus?");
@@ -401,7 +742,7 @@ impl canvas::Program<GameMessage> for Game {
                             }
                             *state = MouseState::LeftDown(position);
                             trace!("Publishing PressedPositionChanged");
-                            Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                            Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                         },
                         MouseState::BothDown(pos) => {
                             if *pos == position {
@@ -409,20 +750,13 @@ impl canvas::Program<GameMessage> for Game {
                             }
                             *state = MouseState::BothDown(position);
                             trace!("Publishing PressedPositionChanged");
-                            Some(canvas::Action::publish(GameMessage::PressedPositionChanged).and_capture())
+                            Some(canvas::Action::publish(GameMessage::PressedPositionChanged(*state)).and_capture())
                         },
                         _ => None,
                     },
                     _ => None,
                 }
             },
-            iced::Event::Keyboard(iced::keyboard::Event::KeyReleased {
-                key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Space),
-                ..
-            }) => {
-                trace!("Space key released, publishing FaceClicked");
-                Some(canvas::Action::publish(GameMessage::FaceClicked).and_capture())
-            },
             _ => None,
         }
     }
@@ -431,10 +765,11 @@ impl canvas::Program<GameMessage> for Game {
         &self,
         state: &Self::State,
         renderer: &iced::Renderer,
-        _theme: &iced::Theme,
+        theme: &iced::Theme,
         bounds: iced::Rectangle,
         _cursor: iced::mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
+        let state = if self.playback { &self.mouse } else { &state.mouse };
         let geom = self.cache.draw(renderer, bounds.size(), |frame| {
             if self.board_area.top_area != iced::Rectangle::default() {
                 if self.board_area.counter_area != iced::Rectangle::default() {
@@ -466,6 +801,14 @@ impl canvas::Program<GameMessage> for Game {
                     }
                 }
 
+                if self.board_area.timer_area != iced::Rectangle::default() {
+                    let seconds = self.timer.elapsed().as_secs().min(Self::TIMER_MAX_SECONDS) as usize;
+                    let digits = [seconds / 100, (seconds / 10) % 10, seconds % 10];
+                    for (rect, digit) in self.board_area.timer_digit_area.iter().zip(digits) {
+                        frame.draw_image(*rect, &self.skin.top_area.counter.digits[digit]);
+                    }
+                }
+
                 if self.board_area.face_area != iced::Rectangle::default() {
                     let face_img = match state {
                         MouseState::LeftDown(Some((usize::MAX, usize::MAX)))
@@ -596,6 +939,14 @@ impl canvas::Program<GameMessage> for Game {
                                         board::BoardState::Won => &self.skin.cell.mine.flagged,
                                         _ => &self.skin.cell.closed,
                                     }
+                                } else if self.board.start_position() == Some((x, y))
+                                    && matches!(
+                                        self.board.state(),
+                                        board::BoardState::InProgress { opened_cells: 0, .. }
+                                    )
+                                    && let Some(start) = &self.skin.cell.start
+                                {
+                                    start
                                 } else {
                                     &self.skin.cell.closed
                                 }
@@ -627,6 +978,19 @@ impl canvas::Program<GameMessage> for Game {
                     );
                 }
             }
+
+            if let Some((x, y)) = self.cursor {
+                let cell_size = self.cell_size as f32;
+                frame.stroke(
+                    &canvas::Path::rectangle(
+                        self.cell_position(x, y) + iced::Vector::new(1.0, 1.0),
+                        iced::Size::new(cell_size - 2.0, cell_size - 2.0),
+                    ),
+                    canvas::Stroke::default()
+                        .with_color(theme.palette().primary)
+                        .with_width(2.0),
+                );
+            }
         });
 
         vec![geom]
@@ -0,0 +1,130 @@
+use crate::config::{BoardPreset, GlobalConfig, GlobalConfigUpdate};
+use log::{debug, info};
+
+#[derive(Debug, Clone)]
+pub enum PresetMessage {
+    /// Start a new game with the size of the preset.
+    Selected(BoardPreset),
+    NameChanged(String),
+    /// Keep the size in the board config as a custom preset.
+    Save,
+    /// Remove the selected custom preset.
+    Delete,
+}
+
+/// The board presets of the board config, the built-in ones and those saved by the player.
+#[derive(Debug, Default)]
+pub struct Presets {
+    name: String,
+    /// The custom preset picked last, which is the one [`PresetMessage::Delete`] removes.
+    selected: Option<String>,
+}
+
+impl Presets {
+    /// Returns `true` if a preset was picked, so that a game with the new board config starts.
+    pub fn update(
+        &mut self,
+        msg: PresetMessage,
+        config: &mut GlobalConfig,
+        config_update: &mut GlobalConfigUpdate,
+    ) -> bool {
+        match msg {
+            PresetMessage::Selected(preset) => {
+                info!("Preset selected: {} {:?}", preset.name, preset.board);
+                config.board = preset.board;
+                config_update.board(config.board);
+                self.selected = Some(preset.name);
+                return true;
+            },
+            PresetMessage::NameChanged(name) => self.name = name,
+            PresetMessage::Save => {
+                let name = self.name.trim().to_string();
+                if name.is_empty() || BoardPreset::is_builtin_name(&name) {
+                    return false;
+                }
+                debug!("Saving preset {}: {:?}", name, config.board);
+                let preset = BoardPreset {
+                    name,
+                    board: config.board,
+                };
+                self.selected = Some(preset.name.clone());
+                match config.presets.iter_mut().find(|saved| saved.name == preset.name) {
+                    Some(saved) => *saved = preset,
+                    None => config.presets.push(preset),
+                }
+                config_update.presets(config.presets.clone());
+                self.name.clear();
+            },
+            PresetMessage::Delete => {
+                let Some(name) = self.selected.take() else {
+                    return false;
+                };
+                let Some(index) = config.presets.iter().position(|preset| preset.name == name) else {
+                    return false;
+                };
+                let preset = config.presets.remove(index);
+                debug!("Deleted preset {}", preset.name);
+                config_update.presets(config.presets.clone());
+            },
+        }
+        false
+    }
+
+    pub fn view_builtin(&self, enable_button: bool) -> iced::Element<'_, PresetMessage> {
+        iced::widget::row(BoardPreset::builtin().map(|preset| {
+            iced::widget::button(
+                iced::widget::text(preset.name.clone())
+                    .size(14)
+                    .align_x(iced::alignment::Horizontal::Center),
+            )
+            .padding([5, 2])
+            .width(iced::FillPortion(1))
+            .on_press_maybe(enable_button.then_some(PresetMessage::Selected(preset)))
+            .into()
+        }))
+        .spacing(4)
+        .into()
+    }
+
+    pub fn view_custom<'a>(
+        &'a self,
+        config: &'a GlobalConfig,
+        enable_button: bool,
+    ) -> iced::Element<'a, PresetMessage> {
+        let can_save = !self.name.trim().is_empty() && !BoardPreset::is_builtin_name(self.name.trim());
+        // The selected preset stops being current once the board config is edited
+        let current = config
+            .presets
+            .iter()
+            .find(|preset| self.selected.as_ref() == Some(&preset.name) && preset.board == config.board)
+            .cloned();
+        // A pick list cannot be disabled, so it offers nothing to pick instead
+        let options = if enable_button { &config.presets[..] } else { &[] };
+
+        iced::widget::column![
+            iced::widget::row![
+                iced::widget::pick_list(options, current.clone(), PresetMessage::Selected)
+                    .placeholder("Custom preset")
+                    .width(iced::FillPortion(2)),
+                iced::widget::button(iced::widget::text("Delete").align_x(iced::alignment::Horizontal::Center))
+                    .width(iced::FillPortion(1))
+                    .on_press_maybe(current.is_some().then_some(PresetMessage::Delete)),
+            ]
+            .spacing(4)
+            .align_y(iced::alignment::Vertical::Center),
+            iced::widget::row![
+                iced::widget::TextInput::new("preset name", &self.name)
+                    .width(iced::FillPortion(2))
+                    .on_input(PresetMessage::NameChanged)
+                    .on_submit_maybe(can_save.then_some(PresetMessage::Save)),
+                iced::widget::button(iced::widget::text("Save").align_x(iced::alignment::Horizontal::Center))
+                    .width(iced::FillPortion(1))
+                    .on_press_maybe(can_save.then_some(PresetMessage::Save)),
+            ]
+            .spacing(4)
+            .align_y(iced::alignment::Vertical::Center),
+        ]
+        .spacing(4)
+        .into()
+    }
+}
@@ -0,0 +1,103 @@
+use super::game::{BoardMessage, MouseState};
+use crate::base::board;
+use std::time::{Duration, Instant};
+
+/// An input of the player, as seen by the game.
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    Board(BoardMessage),
+    /// The buttons held down over the game canvas and the cell under them changed.
+    Mouse(MouseState),
+    ChordMode(board::ChordMode),
+    /// The game was continued after stepping on a mine.
+    Continue,
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Event {
+    /// Time since the first recorded event.
+    pub time: Duration,
+    pub action: Action,
+}
+
+/// Everything the player did in a game, together with its layout, so that it can be played back
+/// on a fresh board.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    started: Option<Instant>,
+    chord_mode: board::ChordMode,
+    layout: Option<board::ImportPack>,
+    events: Vec<Event>,
+    /// The game was continued from a position, so it cannot be played back from a fresh board.
+    resumed: bool,
+}
+
+impl Recording {
+    pub fn new(chord_mode: board::ChordMode) -> Self {
+        Self {
+            started: None,
+            chord_mode,
+            layout: None,
+            events: Vec::new(),
+            resumed: false,
+        }
+    }
+
+    /// Record a game continued from a partially played position, which never gets a layout.
+    pub fn resumed(chord_mode: board::ChordMode) -> Self {
+        Self {
+            resumed: true,
+            ..Self::new(chord_mode)
+        }
+    }
+
+    pub fn record(&mut self, action: Action) {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        self.events.push(Event {
+            time: started.elapsed(),
+            action,
+        });
+    }
+
+    /// Keep the layout of `board` once its mines have been placed.
+    pub fn capture_layout(&mut self, board: &dyn board::Board) {
+        if self.resumed || self.layout.is_some() || board.state() == board::BoardState::NotStarted {
+            return;
+        }
+        self.layout = Some(board::ImportPack {
+            cell_contents: board.cell_contents().clone(),
+            mines: board.mines(),
+            start_position: board.start_position(),
+            cell_states: None,
+            seed: board.board_seed(),
+        });
+    }
+
+    /// The chord mode when the recording started.
+    pub fn chord_mode(&self) -> board::ChordMode {
+        self.chord_mode
+    }
+
+    /// The layout of the game, `None` if no cell has been opened yet.
+    pub fn layout(&self) -> Option<&board::ImportPack> {
+        self.layout.as_ref()
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |event| event.time)
+    }
+
+    /// Time of the first click on the board, when the game timer started.
+    pub fn first_click_time(&self) -> Option<Duration> {
+        self.events
+            .iter()
+            .find(|event| matches!(event.action, Action::Board(..)))
+            .map(|event| event.time)
+    }
+}
@@ -0,0 +1,201 @@
+use super::{
+    game::{Game, GameMessage, MouseState},
+    recording::{Action, Recording},
+};
+use crate::{
+    base::board,
+    ui::{board_area::BoardArea, skin},
+};
+use log::{debug, trace};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplaySpeed {
+    Quarter,
+    Half,
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+    Octuple,
+}
+
+impl ReplaySpeed {
+    pub const ALL: [ReplaySpeed; 6] = [
+        ReplaySpeed::Quarter,
+        ReplaySpeed::Half,
+        ReplaySpeed::Normal,
+        ReplaySpeed::Double,
+        ReplaySpeed::Quadruple,
+        ReplaySpeed::Octuple,
+    ];
+
+    fn factor(self) -> f64 {
+        match self {
+            ReplaySpeed::Quarter => 0.25,
+            ReplaySpeed::Half => 0.5,
+            ReplaySpeed::Normal => 1.0,
+            ReplaySpeed::Double => 2.0,
+            ReplaySpeed::Quadruple => 4.0,
+            ReplaySpeed::Octuple => 8.0,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x", self.factor())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ReplayMessage {
+    Open,
+    Close,
+    TogglePlaying,
+    SetSpeed(ReplaySpeed),
+    /// Jump to the given number of seconds into the recording.
+    Seek(f64),
+    Tick(Instant),
+    Game(GameMessage),
+}
+
+/// Plays a [`Recording`] back on a fresh board with its layout.
+pub struct ReplayViewer {
+    recording: Recording,
+    game: Game,
+    /// Current time of the playback since the first recorded event.
+    position: Duration,
+    /// Index of the first event not played yet.
+    next_event: usize,
+    playing: bool,
+    speed: ReplaySpeed,
+    last_tick: Option<Instant>,
+}
+
+impl ReplayViewer {
+    /// Returns `None` if nothing has been recorded on the board yet.
+    pub fn new(
+        recording: Recording,
+        board_area: BoardArea,
+        cell_size: u32,
+        skin: Arc<skin::Skin>,
+        viewport: iced::Rectangle,
+    ) -> Option<Self> {
+        let board = board::StandardBoard::import(recording.layout()?.clone(), recording.chord_mode())?;
+        let mut game = Game::new_playback(Box::new(board), board_area, cell_size, skin);
+        game.update(GameMessage::ViewportChanged(viewport));
+        debug!(
+            "Opening replay with {} events over {:?}",
+            recording.events().len(),
+            recording.duration()
+        );
+        Some(Self {
+            recording,
+            game,
+            position: Duration::ZERO,
+            next_event: 0,
+            playing: true,
+            speed: ReplaySpeed::default(),
+            last_tick: None,
+        })
+    }
+
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.recording.duration()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn speed(&self) -> ReplaySpeed {
+        self.speed
+    }
+
+    /// Play every event up to the current position.
+    fn advance(&mut self) {
+        while let Some(event) = self.recording.events().get(self.next_event)
+            && event.time <= self.position
+        {
+            let message = match event.action {
+                Action::Board(message) => GameMessage::Board(message),
+                Action::Mouse(mouse) => GameMessage::PressedPositionChanged(mouse),
+                Action::ChordMode(mode) => GameMessage::ChordModeChanged(mode),
+                Action::Continue => GameMessage::Continue,
+                Action::Undo => GameMessage::Undo,
+                Action::Redo => GameMessage::Redo,
+            };
+            self.game.update(message);
+            self.next_event += 1;
+        }
+        // The game timer starts with the first click, like the recorded one did
+        let elapsed = self
+            .recording
+            .first_click_time()
+            .map_or(Duration::ZERO, |start| self.position.saturating_sub(start));
+        self.game.set_playback_time(elapsed);
+    }
+
+    fn seek(&mut self, position: Duration) {
+        trace!("Seeking replay to {:?}", position);
+        if position < self.position {
+            // Events cannot be undone, so play them again from the start
+            self.game.update(GameMessage::Replay);
+            self.game
+                .update(GameMessage::ChordModeChanged(self.recording.chord_mode()));
+            self.game.update(GameMessage::PressedPositionChanged(MouseState::Idle));
+            self.next_event = 0;
+        }
+        self.position = position.min(self.duration());
+        self.advance();
+    }
+
+    pub fn update(&mut self, message: ReplayMessage) {
+        match message {
+            // Opened and closed by the player
+            ReplayMessage::Open | ReplayMessage::Close => {},
+            ReplayMessage::TogglePlaying => {
+                if !self.playing && self.position >= self.duration() {
+                    self.seek(Duration::ZERO);
+                }
+                self.playing = !self.playing;
+                self.last_tick = None;
+            },
+            ReplayMessage::SetSpeed(speed) => {
+                debug!("Replay speed set to {}", speed);
+                self.speed = speed;
+            },
+            ReplayMessage::Seek(seconds) => self.seek(Duration::from_secs_f64(seconds.max(0.0))),
+            ReplayMessage::Tick(now) => {
+                if !self.playing {
+                    return;
+                }
+                let delta = self
+                    .last_tick
+                    .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+                self.last_tick = Some(now);
+                self.position = (self.position + delta.mul_f64(self.speed.factor())).min(self.duration());
+                self.advance();
+                if self.position >= self.duration() {
+                    debug!("Replay finished");
+                    self.playing = false;
+                }
+            },
+            ReplayMessage::Game(message) => {
+                self.game.update(message);
+            },
+        }
+    }
+
+    pub fn view(&self) -> iced::Element<'_, ReplayMessage> {
+        self.game.view().map(ReplayMessage::Game)
+    }
+}
@@ -0,0 +1,118 @@
+use super::game::Game;
+use crate::{base::board, save};
+
+#[derive(Debug, Clone)]
+pub enum SaveMessage {
+    NameChanged(String),
+    Selected(String),
+    Save,
+    Load,
+    Delete,
+    /// Continue the game left when the app was last closed, if any.
+    RestoreAutosave,
+}
+
+/// The games saved by name on this machine.
+#[derive(Debug)]
+pub struct Saves {
+    name: String,
+    games: Vec<String>,
+    selected: Option<String>,
+}
+
+impl Default for Saves {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            games: save::saved_games(),
+            selected: None,
+        }
+    }
+}
+
+impl Saves {
+    /// The saved game to load.
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    /// Returns the error to show to the player, if any.
+    ///
+    /// [`SaveMessage::Load`] and [`SaveMessage::RestoreAutosave`] replace the game, so they are
+    /// left to the player.
+    pub fn update(&mut self, msg: SaveMessage, game: &Game) -> Result<(), String> {
+        match msg {
+            SaveMessage::NameChanged(name) => self.name = name,
+            SaveMessage::Selected(name) => self.selected = Some(name),
+            SaveMessage::Save => {
+                let name = self.name.trim().to_string();
+                let Some(saved) = save::SavedGame::new(game.board(), game.timer().elapsed(), game.is_assisted()) else {
+                    return Ok(());
+                };
+                saved
+                    .save_as(&name)
+                    .map_err(|err| format!("Failed to save the game: {err}"))?;
+                self.games = save::saved_games();
+                self.selected = Some(name);
+                self.name.clear();
+            },
+            SaveMessage::Delete => {
+                let Some(name) = self.selected.take() else {
+                    return Ok(());
+                };
+                let deleted = save::SavedGame::delete(&name);
+                self.games = save::saved_games();
+                deleted.map_err(|err| format!("Failed to delete {name}: {err}"))?;
+            },
+            SaveMessage::Load | SaveMessage::RestoreAutosave => {},
+        }
+        Ok(())
+    }
+
+    pub fn view(&self, game: &Game, enable_button: bool, width: f32) -> iced::Element<'_, SaveMessage> {
+        let can_save = enable_button
+            && !self.name.trim().is_empty()
+            && !matches!(game.board().state(), board::BoardState::NotStarted);
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Saved Games").size(18)),
+                iced::widget::row![
+                    iced::widget::TextInput::new("name", &self.name)
+                        .width(iced::FillPortion(2))
+                        .on_input(SaveMessage::NameChanged)
+                        .on_submit_maybe(can_save.then_some(SaveMessage::Save)),
+                    iced::widget::button(iced::widget::text("Save").align_x(iced::alignment::Horizontal::Center))
+                        .width(iced::FillPortion(1))
+                        .on_press_maybe(can_save.then_some(SaveMessage::Save)),
+                ]
+                .spacing(4)
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::pick_list(&self.games[..], self.selected.clone(), SaveMessage::Selected)
+                    .placeholder("Select a game")
+                    .width(iced::Fill),
+                iced::widget::row![
+                    iced::widget::button(iced::widget::text("Load").align_x(iced::alignment::Horizontal::Center))
+                        .width(iced::FillPortion(1))
+                        .on_press_maybe((enable_button && self.selected.is_some()).then_some(SaveMessage::Load)),
+                    iced::widget::button(iced::widget::text("Delete").align_x(iced::alignment::Horizontal::Center))
+                        .width(iced::FillPortion(1))
+                        .on_press_maybe(self.selected.is_some().then_some(SaveMessage::Delete)),
+                ]
+                .spacing(4),
+            ]
+            .spacing(4)
+            .padding(6)
+            .width(width * 0.9),
+        )
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
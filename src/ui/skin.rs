@@ -2,7 +2,7 @@ use log::{debug, info, trace, warn};
 use std::{
     collections::HashMap,
     fs::{read_dir, read_to_string},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 mod config {
@@ -15,11 +15,30 @@ mod config {
         pub background_color: u32,
         pub highlight_color: u32,
         pub shadow_color: u32,
+        /// A single image the rectangles in [`Image::Sheet`] are cut from, for skins that come
+        /// as one atlas bitmap. SVG is not supported here.
+        pub sheet: Option<String>,
         pub border: Border,
         pub top_area: TopArea,
         pub cell: Cell,
     }
 
+    /// Where an element of the skin is drawn from.
+    #[derive(Clone, Debug, Deserialize)]
+    #[serde(untagged)]
+    pub enum Image {
+        /// An image file in the skin directory.
+        File(String),
+        /// `[x, y, width, height]` of the element in the sheet, in pixels.
+        Sheet([u32; 4]),
+    }
+
+    impl Default for Image {
+        fn default() -> Self {
+            Image::File(String::new())
+        }
+    }
+
     #[derive(Clone, Debug, Default, Deserialize)]
     pub struct Border {
         pub color: u32,
@@ -37,8 +56,8 @@ mod config {
     #[derive(Clone, Debug, Default, Deserialize)]
     pub struct Counter {
         pub background_color: u32,
-        pub minus: String,
-        pub digits: [String; 10],
+        pub minus: Image,
+        pub digits: [Image; 10],
         pub height_scaling: f32,
         pub content_height_scaling: f32,
         pub content_width_scaling: f32,
@@ -47,34 +66,36 @@ mod config {
 
     #[derive(Clone, Debug, Default, Deserialize)]
     pub struct Face {
-        pub normal: String,
-        pub pressed: String,
-        pub win: String,
-        pub lose: String,
+        pub normal: Image,
+        pub pressed: Image,
+        pub win: Image,
+        pub lose: Image,
         pub size_scaling: f32,
     }
 
     #[derive(Clone, Debug, Default, Deserialize)]
     pub struct Cell {
-        pub closed: String,
-        pub pressed: String,
-        pub opening: String,
-        pub numbers: [String; 8],
+        pub closed: Image,
+        pub pressed: Image,
+        pub opening: Image,
+        pub numbers: [Image; 8],
         pub mine: Mine,
+        pub start: Option<Image>,
     }
 
     #[derive(Clone, Debug, Default, Deserialize)]
     pub struct Mine {
-        pub flagged: String,
-        pub unflagged: String,
-        pub blasted: String,
-        pub wrong: String,
+        pub flagged: Image,
+        pub unflagged: Image,
+        pub blasted: Image,
+        pub wrong: Image,
     }
 }
 
 pub type SkinConfig = config::Skin;
 
 mod build {
+    use super::config::Image;
     use iced::widget::image::Handle as ImageHandle;
     use image::ImageReader;
     use log::{debug, error, trace};
@@ -133,6 +154,7 @@ mod build {
         pub opening: ImageHandle,
         pub numbers: [ImageHandle; 8],
         pub mine: Mine,
+        pub start: Option<ImageHandle>,
     }
 
     #[derive(Debug, Clone)]
@@ -150,7 +172,61 @@ mod build {
     }
 
     impl SkinBuilder {
-        fn load_image(&self, file: &str, width: u32, height: u32) -> crate::error::Result<ImageHandle> {
+        fn load_sheet(&self) -> crate::error::Result<Option<image::DynamicImage>> {
+            let Some(file) = &self.config.sheet else {
+                return Ok(None);
+            };
+            let path = self.dir.join(file);
+            trace!("Loading sprite sheet: {}", path.to_string_lossy());
+            let sheet = ImageReader::open(path)
+                .inspect_err(|e| error!("Failed to open sprite sheet: {}", e))?
+                .decode()
+                .inspect_err(|e| error!("Failed to decode sprite sheet: {}", e))?;
+            Ok(Some(sheet))
+        }
+
+        fn load_image(
+            &self,
+            sheet: Option<&image::DynamicImage>,
+            source: &Image,
+            width: u32,
+            height: u32,
+        ) -> crate::error::Result<ImageHandle> {
+            match source {
+                Image::File(file) => self.load_file(file, width, height),
+                &Image::Sheet([x, y, w, h]) => {
+                    let Some(sheet) = sheet else {
+                        error!("Skin '{}' cuts images from a sheet but has none", self.config.name);
+                        return Err(crate::error::Error::InvalidSkin(
+                            "sheet rectangles used without a sheet".to_string(),
+                        ));
+                    };
+                    trace!("Cutting [{x}, {y}, {w}, {h}] from the sprite sheet");
+                    if w == 0
+                        || h == 0
+                        || x.checked_add(w).is_none_or(|right| right > sheet.width())
+                        || y.checked_add(h).is_none_or(|bottom| bottom > sheet.height())
+                    {
+                        error!(
+                            "Rectangle [{x}, {y}, {w}, {h}] is out of the {}x{} sprite sheet",
+                            sheet.width(),
+                            sheet.height()
+                        );
+                        return Err(crate::error::Error::InvalidSkin(format!(
+                            "rectangle [{x}, {y}, {w}, {h}] is out of the {}x{} sheet",
+                            sheet.width(),
+                            sheet.height()
+                        )));
+                    }
+                    let img = sheet
+                        .crop_imm(x, y, w, h)
+                        .resize_exact(width, height, image::imageops::Lanczos3);
+                    Ok(ImageHandle::from_rgba(width, height, img.to_rgba8().into_raw()))
+                },
+            }
+        }
+
+        fn load_file(&self, file: &str, width: u32, height: u32) -> crate::error::Result<ImageHandle> {
             let path = self.dir.join(file);
             trace!("Loading image file: {}", path.to_string_lossy());
             if path
@@ -205,6 +281,8 @@ mod build {
 
         pub fn build(&self, cell_size: u32) -> crate::error::Result<Skin> {
             debug!("Building skin: {}", self.config.name);
+            let sheet = self.load_sheet()?;
+            let load = |source: &Image, width: u32, height: u32| self.load_image(sheet.as_ref(), source, width, height);
             let border = Border {
                 color: iced::Color::from_rgb8(
                     ((self.config.border.color >> 16) & 0xFF) as u8,
@@ -221,7 +299,7 @@ mod build {
                     ((self.config.top_area.counter.background_color >> 8) & 0xFF) as u8,
                     (self.config.top_area.counter.background_color & 0xFF) as u8,
                 ),
-                minus: self.load_image(
+                minus: load(
                     &self.config.top_area.counter.minus,
                     content_width as u32,
                     content_height as u32,
@@ -232,7 +310,7 @@ mod build {
                     .counter
                     .digits
                     .iter()
-                    .map(|file| self.load_image(file, content_width as u32, content_height as u32))
+                    .map(|source| load(source, content_width as u32, content_height as u32))
                     .collect::<Result<Vec<_>, _>>()?
                     .try_into()
                     .unwrap(),
@@ -243,10 +321,10 @@ mod build {
             };
             let size = (self.config.top_area.face.size_scaling * cell_size as f32).ceil();
             let face = Face {
-                normal: self.load_image(&self.config.top_area.face.normal, size as u32, size as u32)?,
-                pressed: self.load_image(&self.config.top_area.face.pressed, size as u32, size as u32)?,
-                win: self.load_image(&self.config.top_area.face.win, size as u32, size as u32)?,
-                lose: self.load_image(&self.config.top_area.face.lose, size as u32, size as u32)?,
+                normal: load(&self.config.top_area.face.normal, size as u32, size as u32)?,
+                pressed: load(&self.config.top_area.face.pressed, size as u32, size as u32)?,
+                win: load(&self.config.top_area.face.win, size as u32, size as u32)?,
+                lose: load(&self.config.top_area.face.lose, size as u32, size as u32)?,
                 size,
             };
             let top_area = TopArea {
@@ -260,25 +338,32 @@ mod build {
                 face,
             };
             let mine = Mine {
-                flagged: self.load_image(&self.config.cell.mine.flagged, cell_size, cell_size)?,
-                unflagged: self.load_image(&self.config.cell.mine.unflagged, cell_size, cell_size)?,
-                blasted: self.load_image(&self.config.cell.mine.blasted, cell_size, cell_size)?,
-                wrong: self.load_image(&self.config.cell.mine.wrong, cell_size, cell_size)?,
+                flagged: load(&self.config.cell.mine.flagged, cell_size, cell_size)?,
+                unflagged: load(&self.config.cell.mine.unflagged, cell_size, cell_size)?,
+                blasted: load(&self.config.cell.mine.blasted, cell_size, cell_size)?,
+                wrong: load(&self.config.cell.mine.wrong, cell_size, cell_size)?,
             };
             let cell = Cell {
-                closed: self.load_image(&self.config.cell.closed, cell_size, cell_size)?,
-                pressed: self.load_image(&self.config.cell.pressed, cell_size, cell_size)?,
-                opening: self.load_image(&self.config.cell.opening, cell_size, cell_size)?,
+                closed: load(&self.config.cell.closed, cell_size, cell_size)?,
+                pressed: load(&self.config.cell.pressed, cell_size, cell_size)?,
+                opening: load(&self.config.cell.opening, cell_size, cell_size)?,
                 numbers: self
                     .config
                     .cell
                     .numbers
                     .iter()
-                    .map(|file| self.load_image(file, cell_size, cell_size))
+                    .map(|source| load(source, cell_size, cell_size))
                     .collect::<Result<Vec<_>, _>>()?
                     .try_into()
                     .unwrap(),
                 mine,
+                start: self
+                    .config
+                    .cell
+                    .start
+                    .as_ref()
+                    .map(|source| load(source, cell_size, cell_size))
+                    .transpose()?,
             };
             debug!("Skin built successfully: {}", self.config.name);
             Ok(Skin {
@@ -304,20 +389,88 @@ mod build {
                 cell,
             })
         }
+
+        /// `skin` laid out for `cell_size` without rasterising the images again, they are scaled
+        /// when drawn instead.
+        ///
+        /// Much faster than [`SkinBuilder::build`], whose result at the same size has the same
+        /// layout and should replace the preview once the size settles.
+        pub fn preview(&self, skin: &Skin, cell_size: u32) -> Skin {
+            let scaled = |scaling: f32| (scaling * cell_size as f32).ceil();
+            let mut preview = skin.clone();
+            preview.border.width = scaled(self.config.border.width_scaling);
+            preview.top_area.height = scaled(self.config.top_area.height_scaling);
+            preview.top_area.face.size = scaled(self.config.top_area.face.size_scaling);
+            let counter = &mut preview.top_area.counter;
+            counter.height = scaled(self.config.top_area.counter.height_scaling);
+            counter.content_height = scaled(self.config.top_area.counter.content_height_scaling);
+            counter.content_width = scaled(self.config.top_area.counter.content_width_scaling);
+            counter.content_gap = scaled(self.config.top_area.counter.content_gap_scaling);
+            preview
+        }
     }
 }
 
 pub use build::{Skin, SkinBuilder};
 
+/// A skin directory that could not be loaded, kept to be shown to the user.
+#[derive(Debug, Clone)]
+pub struct SkinLoadError {
+    pub dir: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for SkinLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.dir.to_string_lossy(), self.reason)
+    }
+}
+
 #[derive(Debug)]
 pub struct SkinManager {
     skins: HashMap<String, SkinBuilder>,
+    errors: Vec<SkinLoadError>,
 }
 
 impl SkinManager {
-    pub fn new(root: impl AsRef<Path>) -> crate::error::Result<Self> {
-        let mut skins = HashMap::new();
-        for entry in read_dir(root)? {
+    /// Where users put their own skins, next to the config.
+    pub fn user_dir() -> PathBuf {
+        crate::save::save_dir().join("skins")
+    }
+
+    /// Scan every skin directory under each of `roots`. A skin found in a later root replaces the
+    /// one of the same name found before, so user skins should come after the bundled ones.
+    pub fn new<P: AsRef<Path>>(roots: impl IntoIterator<Item = P>) -> crate::error::Result<Self> {
+        let mut manager = Self {
+            skins: HashMap::new(),
+            errors: Vec::new(),
+        };
+        for root in roots {
+            manager.scan(root.as_ref());
+        }
+
+        if manager.skins.is_empty() {
+            Err(crate::error::Error::MissingResource("skin".to_string()))
+        } else {
+            Ok(manager)
+        }
+    }
+
+    fn scan(&mut self, root: &Path) {
+        debug!("Scanning skins in {}", root.to_string_lossy());
+        let entries = match read_dir(root) {
+            Ok(a) => a,
+            // Users have no skin directory until they add a skin
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("Skin directory not found: {}, skipped", root.to_string_lossy());
+                return;
+            },
+            Err(e) => {
+                warn!("Failed to read skin directory {}: {e}, skipped", root.to_string_lossy());
+                return;
+            },
+        };
+        for entry in entries {
             let entry = match entry {
                 Ok(a) => a,
                 Err(e) => {
@@ -355,11 +508,17 @@ impl SkinManager {
                     },
                 }
 
-                let skin_config = read_to_string(skin_config)?;
-                let skin_config: SkinConfig = match toml::from_str(&skin_config) {
+                let skin_config = match read_to_string(&skin_config)
+                    .map_err(crate::error::Error::from)
+                    .and_then(|data| toml::from_str::<SkinConfig>(&data).map_err(crate::error::Error::from))
+                {
                     Ok(a) => a,
                     Err(e) => {
                         warn!("Failed to load skin: {e}, skipped");
+                        self.errors.push(SkinLoadError {
+                            dir: entry.path(),
+                            reason: e.to_string(),
+                        });
                         continue;
                     },
                 };
@@ -367,7 +526,15 @@ impl SkinManager {
                 info!("Loaded skin: {}", skin_config.name);
                 trace!("{skin_config:?}");
 
-                skins.insert(
+                if let Some(replaced) = self.skins.get(&skin_config.name) {
+                    info!(
+                        "Skin '{}' in {} replaces the one in {}",
+                        skin_config.name,
+                        entry.path().to_string_lossy(),
+                        replaced.dir.to_string_lossy()
+                    );
+                }
+                self.skins.insert(
                     skin_config.name.clone(),
                     SkinBuilder {
                         dir: entry.path(),
@@ -378,15 +545,14 @@ impl SkinManager {
                 trace!("Skipping non-directory entry: {}", entry.path().to_string_lossy());
             }
         }
-
-        if skins.is_empty() {
-            Err(crate::error::Error::MissingResource("skin".to_string()))
-        } else {
-            Ok(Self { skins })
-        }
     }
 
     pub fn skins(&self) -> &HashMap<String, SkinBuilder> {
         &self.skins
     }
+
+    /// The skin directories that failed to load.
+    pub fn errors(&self) -> &[SkinLoadError] {
+        &self.errors
+    }
 }
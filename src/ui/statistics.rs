@@ -0,0 +1,346 @@
+use crate::history::{self, GameRecord};
+use iced::widget::canvas;
+use log::{debug, trace};
+
+#[derive(Debug, Clone)]
+pub enum StatisticsMessage {
+    BoardSelected(String),
+}
+
+/// Results of the recorded games on one board.
+#[derive(Debug, Clone)]
+struct BoardSummary {
+    name: String,
+    games: usize,
+    wins: usize,
+    best: Option<GameRecord>,
+    best_bbbv_per_second: Option<f64>,
+}
+
+impl BoardSummary {
+    fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.games as f64
+    }
+}
+
+/// Times of the clean wins on one board, in the order they were played.
+struct TrendChart {
+    times: Vec<f64>,
+    cache: canvas::Cache,
+}
+
+/// Personal bests and trends from the game history, see [`history`].
+pub struct StatisticsPage {
+    records: Vec<GameRecord>,
+    summaries: Vec<BoardSummary>,
+    selected: Option<String>,
+    chart: TrendChart,
+}
+
+impl StatisticsPage {
+    const CHART_WIDTH: f32 = 640.0;
+    const CHART_HEIGHT: f32 = 320.0;
+
+    pub fn new() -> Self {
+        let mut page = Self {
+            records: Vec::new(),
+            summaries: Vec::new(),
+            selected: None,
+            chart: TrendChart {
+                times: Vec::new(),
+                cache: canvas::Cache::new(),
+            },
+        };
+        page.reload();
+        page
+    }
+
+    /// Read the history again, e.g. after more games have been played.
+    pub fn reload(&mut self) {
+        self.records = history::load();
+        let mut summaries: Vec<BoardSummary> = Vec::new();
+        for record in &self.records {
+            let name = record.board_name();
+            let index = match summaries.iter().position(|summary| summary.name == name) {
+                Some(index) => index,
+                None => {
+                    summaries.push(BoardSummary {
+                        name,
+                        games: 0,
+                        wins: 0,
+                        best: None,
+                        best_bbbv_per_second: None,
+                    });
+                    summaries.len() - 1
+                },
+            };
+            let summary = &mut summaries[index];
+            summary.games += 1;
+            if record.won {
+                summary.wins += 1;
+            }
+            if record.is_clean_win() {
+                if summary.best.as_ref().is_none_or(|best| record.time < best.time) {
+                    summary.best = Some(record.clone());
+                }
+                let bbbv_per_second = record.bbbv_per_second();
+                if summary.best_bbbv_per_second.is_none_or(|best| bbbv_per_second > best) {
+                    summary.best_bbbv_per_second = Some(bbbv_per_second);
+                }
+            }
+        }
+        // The most played boards first
+        summaries.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.name.cmp(&b.name)));
+        self.summaries = summaries;
+
+        if self
+            .selected
+            .as_ref()
+            .is_none_or(|selected| !self.summaries.iter().any(|summary| &summary.name == selected))
+        {
+            self.selected = self.summaries.first().map(|summary| summary.name.clone());
+        }
+        self.update_chart();
+    }
+
+    fn update_chart(&mut self) {
+        self.chart.times = match &self.selected {
+            Some(selected) => self
+                .records
+                .iter()
+                .filter(|record| record.is_clean_win() && &record.board_name() == selected)
+                .map(|record| record.time.as_secs_f64())
+                .collect(),
+            None => Vec::new(),
+        };
+        trace!("Trend chart has {} point(s)", self.chart.times.len());
+        self.chart.cache.clear();
+    }
+
+    pub fn update(&mut self, message: StatisticsMessage) {
+        trace!("StatisticsMessage received: {:?}", message);
+        match message {
+            StatisticsMessage::BoardSelected(name) => {
+                debug!("Statistics board selected: {}", name);
+                self.selected = Some(name);
+                self.update_chart();
+            },
+        }
+    }
+
+    pub fn view_sidebar(&self, width: f32) -> iced::Element<'_, StatisticsMessage> {
+        let names: Vec<String> = self.summaries.iter().map(|summary| summary.name.clone()).collect();
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::row![
+                    iced::widget::text("Board:").size(16).width(iced::FillPortion(1)),
+                    iced::widget::pick_list(names, self.selected.clone(), StatisticsMessage::BoardSelected)
+                        .width(iced::FillPortion(2))
+                ]
+                .align_y(iced::alignment::Vertical::Center),
+                iced::widget::text(format!("{} game(s) recorded", self.records.len())).size(14),
+                iced::widget::text("Bests and trends only count games won without undo or the solver.").size(14),
+            ]
+            .spacing(4)
+            .padding(4),
+        )
+        .width(iced::Length::Fixed(width))
+        .into()
+    }
+
+    pub fn view(&self) -> iced::Element<'_, StatisticsMessage> {
+        let cell = |value: String| iced::widget::text(value).size(14).width(iced::FillPortion(1));
+        let header = iced::widget::row![
+            cell("Board".to_string()),
+            cell("Games".to_string()),
+            cell("Won".to_string()),
+            cell("Win Rate".to_string()),
+            cell("Best".to_string()),
+            cell("Best 3BV/s".to_string()),
+        ];
+        let rows = self.summaries.iter().map(|summary| {
+            iced::widget::row![
+                cell(summary.name.clone()),
+                cell(summary.games.to_string()),
+                cell(summary.wins.to_string()),
+                cell(format!("{:.1}%", summary.win_rate() * 100.0)),
+                cell(
+                    summary
+                        .best
+                        .as_ref()
+                        .map_or_else(|| "-".to_string(), |best| format!("{:.2} s", best.time.as_secs_f64()))
+                ),
+                cell(
+                    summary
+                        .best_bbbv_per_second
+                        .map_or_else(|| "-".to_string(), |best| format!("{:.3}", best))
+                ),
+            ]
+            .into()
+        });
+
+        let table = iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(iced::widget::text("Personal Bests").size(18)),
+                header,
+                iced::widget::rule::horizontal(1),
+            ]
+            .extend(rows)
+            .spacing(4)
+            .padding(6),
+        )
+        .width(Self::CHART_WIDTH)
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        });
+
+        let chart = iced::widget::container(
+            iced::widget::column![
+                iced::widget::center_x(
+                    iced::widget::text(match &self.selected {
+                        Some(selected) => format!("Trend: {}", selected),
+                        None => "Trend".to_string(),
+                    })
+                    .size(18)
+                ),
+                canvas::Canvas::new(&self.chart)
+                    .width(Self::CHART_WIDTH - 12.0)
+                    .height(Self::CHART_HEIGHT),
+            ]
+            .spacing(4)
+            .padding(6),
+        )
+        .width(Self::CHART_WIDTH)
+        .style(move |theme: &iced::Theme| iced::widget::container::Style {
+            border: iced::Border {
+                color: theme.palette().primary,
+                width: 2.0,
+                radius: iced::border::radius(4.0),
+            },
+            ..Default::default()
+        });
+
+        iced::widget::column![table, chart].spacing(8).padding(8).into()
+    }
+}
+
+impl TrendChart {
+    /// Room around the plot for the axis labels.
+    const MARGIN: f32 = 40.0;
+}
+
+impl canvas::Program<StatisticsMessage> for TrendChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &iced::Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let geom = self.cache.draw(renderer, bounds.size(), |frame| {
+            let palette = theme.palette();
+            let label = |frame: &mut canvas::Frame, content: String, position: iced::Point| {
+                frame.fill_text(canvas::Text {
+                    content,
+                    position,
+                    color: palette.text,
+                    size: 12.0.into(),
+                    ..Default::default()
+                });
+            };
+
+            if self.times.is_empty() {
+                label(
+                    frame,
+                    "No clean win on this board yet".to_string(),
+                    iced::Point::new(Self::MARGIN, bounds.height / 2.0),
+                );
+                return;
+            }
+
+            let plot = iced::Rectangle::new(
+                iced::Point::new(Self::MARGIN, Self::MARGIN / 2.0),
+                iced::Size::new(bounds.width - Self::MARGIN * 1.5, bounds.height - Self::MARGIN * 1.5),
+            );
+            let axes = canvas::Path::new(|builder| {
+                builder.move_to(plot.position());
+                builder.line_to(iced::Point::new(plot.x, plot.y + plot.height));
+                builder.line_to(iced::Point::new(plot.x + plot.width, plot.y + plot.height));
+            });
+            frame.stroke(
+                &axes,
+                canvas::Stroke::default().with_color(palette.text).with_width(1.0),
+            );
+
+            let slowest = self.times.iter().copied().fold(f64::MIN, f64::max);
+            let fastest = self.times.iter().copied().fold(f64::MAX, f64::min);
+            // Leave some room when every time is the same
+            let range = (slowest - fastest).max(1.0);
+            let point = |index: usize, time: f64| {
+                let x = if self.times.len() == 1 {
+                    plot.x + plot.width / 2.0
+                } else {
+                    plot.x + plot.width * index as f32 / (self.times.len() - 1) as f32
+                };
+                let y = plot.y + plot.height * (1.0 - ((time - fastest) / range) as f32);
+                iced::Point::new(x, y)
+            };
+
+            let times = canvas::Path::new(|builder| {
+                for (index, &time) in self.times.iter().enumerate() {
+                    if index == 0 {
+                        builder.move_to(point(index, time));
+                    } else {
+                        builder.line_to(point(index, time));
+                    }
+                }
+            });
+            frame.stroke(
+                &times,
+                canvas::Stroke::default().with_color(palette.primary).with_width(1.5),
+            );
+            for (index, &time) in self.times.iter().enumerate() {
+                frame.fill(&canvas::Path::circle(point(index, time), 2.5), palette.primary);
+            }
+
+            // The personal best as it improved over time
+            let bests = canvas::Path::new(|builder| {
+                let mut best = f64::MAX;
+                for (index, &time) in self.times.iter().enumerate() {
+                    best = best.min(time);
+                    if index == 0 {
+                        builder.move_to(point(index, best));
+                    } else {
+                        builder.line_to(point(index, best));
+                    }
+                }
+            });
+            frame.stroke(
+                &bests,
+                canvas::Stroke::default().with_color(palette.success).with_width(2.0),
+            );
+
+            label(frame, format!("{:.0} s", slowest), iced::Point::new(2.0, plot.y - 6.0));
+            label(
+                frame,
+                format!("{:.0} s", fastest),
+                iced::Point::new(2.0, plot.y + plot.height - 6.0),
+            );
+            label(
+                frame,
+                format!("{} win(s), best in green", self.times.len()),
+                iced::Point::new(plot.x, plot.y + plot.height + 4.0),
+            );
+        });
+
+        vec![geom]
+    }
+}